@@ -45,18 +45,13 @@ let
     };
 
     keymap = {
-      prev_day = "Left";
-      cur_day = "Up";
-      next_day = "Right";
-
-      selector = {
-        Char = "o";
-      };
-      settings = {
-        Char = "s";
-      };
-      exit = {
-        Char = "q";
+      normal = {
+        prev_day = "Left";
+        cur_day = "Up";
+        next_day = "Right";
+        selector = "o";
+        settings = "s";
+        exit = "q";
       };
     };
   };
@@ -1,40 +1,105 @@
-use crate::utils::AppDate;
-use log::{debug, info};
+use crate::{config::DEFAULT_PROFILE, utils::AppDate};
+use log::{debug, info, warn};
 use osars::models::Schedule;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+/// On-disk schedule cache.
+///
+/// Files live in `{root}/{provider}/college-{id}/{group_id}-{date}.json`,
+/// where `provider` is derived from the API URL, so the same group id on
+/// two different OpenScheduleApi instances never shares entries. College,
+/// campus and group lists are kept per provider in `{provider}/lists/`.
+/// Profiles other than the default one get the same layout below
+/// `{root}/profiles/{name}/`.
+#[derive(Clone)]
 pub struct CacheManager {
+    root: PathBuf,
+    profile_dir: PathBuf,
+    provider_dir: PathBuf,
     dir: PathBuf,
     ttl: u64,
+    max_stale_age: u64,
     group_id: Option<u32>,
 }
 
 impl CacheManager {
-    pub async fn new(ttl: u64) -> anyhow::Result<Self> {
+    pub async fn new(root: PathBuf, ttl: u64, max_stale_age: u64) -> anyhow::Result<Self> {
         info!("Initializing CacheManager with TTL: {}", ttl);
-        let dir = dirs::cache_dir()
-            .unwrap_or_else(|| ".".into())
-            .join("osatui");
-        debug!("Cache directory: {:?}", dir);
+        debug!("Cache directory: {:?}", root);
 
-        tokio::fs::create_dir_all(&dir).await?;
-        debug!("Cache directory created: {:?}", dir);
+        tokio::fs::create_dir_all(&root).await?;
+        debug!("Cache directory created: {:?}", root);
         Ok(Self {
-            dir,
+            profile_dir: root.clone(),
+            provider_dir: root.clone(),
+            dir: root.clone(),
+            root,
             ttl,
+            max_stale_age,
             group_id: None,
         })
     }
 
-    pub fn update_ttl(&mut self, ttl: u64) {
+    pub fn update_ttl(&mut self, ttl: u64, max_stale_age: u64) {
         self.ttl = ttl;
+        self.max_stale_age = max_stale_age;
     }
 
     pub fn set_group_id(&mut self, group_id: u32) {
         self.group_id = Some(group_id);
     }
 
+    /// Points the cache at the directory of `profile`, `api_url` and
+    /// `college_id`.
+    pub fn set_namespace(&mut self, profile: &str, api_url: &str, college_id: Option<u32>) {
+        self.profile_dir = if profile == DEFAULT_PROFILE {
+            self.root.clone()
+        } else {
+            self.root.join("profiles").join(path_segment(profile))
+        };
+        self.provider_dir = self.profile_dir.join(provider_slug(api_url));
+        self.dir = match college_id {
+            Some(college_id) => self.provider_dir.join(format!("college-{}", college_id)),
+            None => self.provider_dir.clone(),
+        };
+        debug!("Cache namespace: {:?}", self.dir);
+    }
+
+    /// Moves entries written before the cache was namespaced, which sit
+    /// directly in the root, into the current namespace. They can only have
+    /// come from the configured provider of the default profile, and once
+    /// moved the root holds no more of them, so this runs once. Only names
+    /// of schedule entries are moved, as the root may be a directory shared
+    /// with other programs.
+    pub async fn migrate_flat_files(&self) -> anyhow::Result<()> {
+        if self.dir == self.root || self.profile_dir != self.root {
+            return Ok(());
+        }
+
+        let mut entries = tokio::fs::read_dir(&self.root).await?;
+        let mut moved = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let name = entry.file_name();
+            if !path.is_file() || !name.to_str().is_some_and(is_schedule_file_name) {
+                continue;
+            }
+            if moved == 0 {
+                tokio::fs::create_dir_all(&self.dir).await?;
+            }
+            match tokio::fs::rename(&path, self.dir.join(name)).await {
+                Ok(()) => moved += 1,
+                Err(e) => warn!("Failed to migrate cache file {:?}: {}", path, e),
+            }
+        }
+
+        if moved > 0 {
+            info!("Migrated {} cache files into {:?}", moved, self.dir);
+        }
+        Ok(())
+    }
+
     fn cache_file_name(&self, date: &AppDate) -> String {
         match self.group_id {
             Some(gid) => format!("{}-{}.json", gid, date.iso()),
@@ -42,7 +107,25 @@ impl CacheManager {
         }
     }
 
-    pub async fn get(&self, date: &AppDate) -> anyhow::Result<Option<Vec<Schedule>>> {
+    /// Returns the entry for `date` together with its age in seconds if it
+    /// is younger than the TTL.
+    pub async fn get(&self, date: &AppDate) -> anyhow::Result<Option<(Vec<Schedule>, u64)>> {
+        Ok(self.read(date).await?.filter(|(_, age)| *age <= self.ttl))
+    }
+
+    /// Returns the entry for `date` together with its age in seconds, even
+    /// past the TTL, as long as it is within the maximum stale age.
+    pub async fn get_stale(&self, date: &AppDate) -> anyhow::Result<Option<(Vec<Schedule>, u64)>> {
+        if self.max_stale_age == 0 {
+            return Ok(None);
+        }
+        Ok(self
+            .read(date)
+            .await?
+            .filter(|(_, age)| *age <= self.max_stale_age))
+    }
+
+    async fn read(&self, date: &AppDate) -> anyhow::Result<Option<(Vec<Schedule>, u64)>> {
         let file_name = self.cache_file_name(date);
         let path = self.dir.join(&file_name);
 
@@ -52,9 +135,14 @@ impl CacheManager {
 
         let metadata = tokio::fs::metadata(&path).await?;
         let modified = metadata.modified()?;
-        let age = SystemTime::now().duration_since(modified)?.as_secs();
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
 
-        if age > self.ttl {
+        // Expired entries are kept for offline use until they are too old
+        // to be useful even then.
+        if age > self.ttl.max(self.max_stale_age) {
             tokio::fs::remove_file(&path).await?;
             return Ok(None);
         }
@@ -62,13 +150,14 @@ impl CacheManager {
         let content = tokio::fs::read_to_string(&path).await?;
         let data: Vec<Schedule> = serde_json::from_str(&content)?;
 
-        Ok(Some(data))
+        Ok(Some((data, age)))
     }
 
     pub async fn set(&self, date: &AppDate, data: &[Schedule]) -> anyhow::Result<()> {
         let file_name = self.cache_file_name(date);
         let path = self.dir.join(&file_name);
         let content = serde_json::to_string_pretty(data)?;
+        tokio::fs::create_dir_all(&self.dir).await?;
         tokio::fs::write(&path, content).await?;
         Ok(())
     }
@@ -90,6 +179,44 @@ impl CacheManager {
         Ok(())
     }
 
+    /// Returns the serialized list stored under `key` with its age in
+    /// seconds. Lists are never expired here: the caller decides whether an
+    /// old list is still good enough.
+    pub async fn get_list(&self, key: &str) -> anyhow::Result<Option<(Vec<u8>, u64)>> {
+        let path = self.lists_dir().join(format!("{}.json", key));
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let modified = tokio::fs::metadata(&path).await?.modified()?;
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let data = tokio::fs::read(&path).await?;
+
+        Ok(Some((data, age)))
+    }
+
+    pub async fn set_list(&self, key: &str, data: &[u8]) -> anyhow::Result<()> {
+        let dir = self.lists_dir();
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::write(dir.join(format!("{}.json", key)), data).await?;
+        Ok(())
+    }
+
+    pub async fn clear_lists(&self) -> anyhow::Result<()> {
+        let dir = self.lists_dir();
+        if dir.exists() {
+            tokio::fs::remove_dir_all(dir).await?;
+        }
+        Ok(())
+    }
+
+    fn lists_dir(&self) -> PathBuf {
+        self.provider_dir.join("lists")
+    }
+
     pub async fn clear_group(&self, group_id: u32) -> anyhow::Result<()> {
         if !self.dir.exists() {
             return Ok(());
@@ -110,3 +237,58 @@ impl CacheManager {
         Ok(())
     }
 }
+
+/// Whether `name` is that of a schedule entry, `{group_id}-{dd-mm-yyyy}.json`.
+fn is_schedule_file_name(name: &str) -> bool {
+    let Some((group_id, date)) = name
+        .strip_suffix(".json")
+        .and_then(|stem| stem.split_once('-'))
+    else {
+        return false;
+    };
+    group_id.parse::<u32>().is_ok()
+        && date.len() == 10
+        && chrono::NaiveDate::parse_from_str(date, "%d-%m-%Y").is_ok()
+}
+
+/// Directory name for the provider behind `api_url`: a readable slug of the
+/// host and path followed by a hash of the full URL, e.g.
+/// `api-thisishyum-ru-schedule-api-tyumen-c1b788f6`.
+pub fn provider_slug(api_url: &str) -> String {
+    let url = api_url.trim().trim_end_matches('/');
+    let readable = url.split_once("://").map_or(url, |(_, rest)| rest);
+
+    let mut slug = String::new();
+    for c in readable.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(48);
+    let slug = slug.trim_end_matches('-');
+
+    format!("{}-{:08x}", slug, fnv1a(url.as_bytes()) as u32)
+}
+
+/// Keeps a profile name usable as a single directory name.
+fn path_segment(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// FNV-1a, used because it is stable across Rust versions and platforms,
+/// unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
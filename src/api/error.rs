@@ -0,0 +1,79 @@
+use thiserror::Error;
+
+/// Failures of the schedule API, classified so the UI can react to each
+/// kind differently instead of showing a generic message.
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("Network unreachable: {0}")]
+    Offline(String),
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Server responded with {status}: {message}")]
+    Http { status: u16, message: String },
+
+    #[error("No lessons on this day")]
+    EmptyDay,
+
+    #[error("Failed to decode the server response: {0}")]
+    Decode(String),
+
+    #[error("No group selected")]
+    MissingGroup,
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl ApiError {
+    /// Whether the server could not be reached at all, so local data is
+    /// the best that can be shown.
+    pub fn is_network(&self) -> bool {
+        matches!(self, ApiError::Offline(_) | ApiError::Timeout)
+    }
+}
+
+impl From<osars::Error> for ApiError {
+    fn from(error: osars::Error) -> Self {
+        match error {
+            osars::Error::Reqwest(e) if e.is_timeout() => ApiError::Timeout,
+            osars::Error::Reqwest(e) if e.is_decode() => ApiError::Decode(e.to_string()),
+            osars::Error::Reqwest(e) => match e.status() {
+                Some(status) => ApiError::Http {
+                    status: status.as_u16(),
+                    message: e.to_string(),
+                },
+                None => ApiError::Offline(e.to_string()),
+            },
+            osars::Error::Api {
+                status_code,
+                message,
+            } => ApiError::Http {
+                status: status_code,
+                message,
+            },
+            osars::Error::NotFound(message) => ApiError::Http {
+                status: 404,
+                message,
+            },
+            osars::Error::Unauthorized => ApiError::Http {
+                status: 401,
+                message: "Authentication required".to_string(),
+            },
+            osars::Error::Serialization(e) => ApiError::Decode(e.to_string()),
+            osars::Error::Validation(message)
+            | osars::Error::Config(message)
+            | osars::Error::InvalidUrl(message) => ApiError::InvalidRequest(message),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(error: serde_json::Error) -> Self {
+        ApiError::Decode(error.to_string())
+    }
+}
@@ -1,14 +1,52 @@
 pub mod cache;
+pub mod error;
 
 use crate::{config::Config, utils::AppDate};
 use cache::CacheManager;
-use log::{debug, info};
+pub use error::ApiError;
+use log::{debug, info, warn};
 use osars::{
     Client,
     models::{Campus, College, Group, Schedule},
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+/// Monday through Saturday: the days of a study week.
+pub const WEEK_DAYS: i64 = 6;
+
+/// How many campuses' group lists `get_all_groups` requests at a time.
+const GROUPS_CONCURRENCY: usize = 4;
+
+/// A schedule as returned by `ApiClient::fetch`.
+#[derive(Debug, Clone)]
+pub struct Fetched {
+    pub schedules: Vec<Schedule>,
+    /// Seconds since the schedule was received from the API: `0` for a
+    /// request made now, the age of the entry when served from the cache.
+    pub age: u64,
+    /// Age in seconds of an expired cache entry served because the API
+    /// was unreachable; `None` for fresh data.
+    pub stale_age: Option<u64>,
+}
+
+impl Fetched {
+    fn fresh(schedules: Vec<Schedule>, age: u64) -> Self {
+        Self {
+            schedules,
+            age,
+            stale_age: None,
+        }
+    }
+}
+
+type ListsCache = Arc<Mutex<HashMap<String, (Vec<u8>, u64)>>>;
 
+#[derive(Clone)]
 pub struct ApiClient {
     config: Config,
     client: Client,
@@ -16,16 +54,22 @@ pub struct ApiClient {
     campus_id: Option<u32>,
     group_id: Option<u32>,
     cache: Option<CacheManager>,
-    lists_cache: HashMap<String, (Vec<u8>, u64)>,
+    lists_cache: ListsCache,
     lists_cache_ttl: u64,
 }
 
 impl ApiClient {
     pub async fn new(config: Config) -> anyhow::Result<Self> {
+        let cache_dir = config.cache_dir();
+        Self::with_cache_dir(config, cache_dir).await
+    }
+
+    /// Like [`ApiClient::new`], with the schedule cache kept in `cache_dir`.
+    pub async fn with_cache_dir(config: Config, cache_dir: PathBuf) -> anyhow::Result<Self> {
         info!("Initialization API client");
         let mut cache = if config.cache_enabled() {
             debug!("Cache enabled, initializing CacheManager");
-            Some(CacheManager::new(config.cache_ttl()).await?)
+            Some(CacheManager::new(cache_dir, config.cache_ttl(), config.max_stale_age()).await?)
         } else {
             info!("Cache disabled");
             None
@@ -43,23 +87,34 @@ impl ApiClient {
         if let Some(ref mut c) = cache {
             debug!("Setting group_id in CacheManager: {}", group_id);
             c.set_group_id(group_id);
+            c.set_namespace(config.active_profile(), config.api_url(), Some(college_id));
+            if let Err(e) = c.migrate_flat_files().await {
+                warn!("Failed to migrate cache files: {}", e);
+            }
         }
 
         Ok(Self {
+            lists_cache_ttl: config.lists_cache_ttl(),
             config,
             client: client.with_college(college_id),
             college_id: Some(college_id),
             campus_id: Some(campus_id),
             group_id: Some(group_id),
             cache,
-            lists_cache: HashMap::new(),
-            lists_cache_ttl: 300,
+            lists_cache: Arc::default(),
         })
     }
 
     pub async fn new_base(config: Config) -> anyhow::Result<Self> {
         let cache = if config.cache_enabled() {
-            Some(CacheManager::new(config.cache_ttl()).await?)
+            let mut cache = CacheManager::new(
+                config.cache_dir(),
+                config.cache_ttl(),
+                config.max_stale_age(),
+            )
+            .await?;
+            cache.set_namespace(config.active_profile(), config.api_url(), None);
+            Some(cache)
         } else {
             None
         };
@@ -71,26 +126,45 @@ impl ApiClient {
             campus_id: None,
             group_id: None,
             cache,
-            lists_cache: HashMap::new(),
-            lists_cache_ttl: 300,
+            lists_cache: Arc::default(),
+            lists_cache_ttl: config.lists_cache_ttl(),
         })
     }
 
     pub async fn reconfigure(&mut self, config: &Config) -> anyhow::Result<()> {
         let old_group_id = self.group_id;
+        let same_profile = config.active_profile() == self.config.active_profile();
+
+        // The in-memory lists are not keyed by provider.
+        if config.api_url() != self.config.api_url() {
+            self.lists_cache.lock().unwrap().clear();
+        }
 
         self.config = config.clone();
         self.client = Client::new(config.api_url());
+        self.lists_cache_ttl = config.lists_cache_ttl();
 
         if let Some(cache) = &mut self.cache {
-            cache.update_ttl(config.cache_ttl());
+            cache.update_ttl(config.cache_ttl(), config.max_stale_age());
         }
 
         let (college_id, campus_id, group_id) =
             (config.college_id(), config.campus_id(), config.group_id());
 
+        // The old group's entries live in the old namespace, so they are
+        // dropped before the cache is pointed at the new one. Switching
+        // profiles keeps them: the other profile is likely used again.
+        if same_profile
+            && old_group_id != Some(group_id)
+            && let Some(cache) = &self.cache
+            && let Some(old_gid) = old_group_id
+        {
+            cache.clear_group(old_gid).await?;
+        }
+
         if let Some(ref mut c) = self.cache {
             c.set_group_id(group_id);
+            c.set_namespace(config.active_profile(), config.api_url(), Some(college_id));
         }
 
         self.client = self.client.clone().with_college(college_id);
@@ -98,118 +172,253 @@ impl ApiClient {
         self.campus_id = Some(campus_id);
         self.group_id = Some(group_id);
 
-        if old_group_id != Some(group_id)
-            && let Some(cache) = &self.cache
-            && let Some(old_gid) = old_group_id
-        {
-            cache.clear_group(old_gid).await?;
-        }
-
         Ok(())
     }
 
-    pub async fn get_colleges(&mut self) -> anyhow::Result<Vec<College>> {
+    pub async fn get_colleges(&self) -> Result<Vec<College>, ApiError> {
         let api_url = self.config.api_url().to_string();
         self.get_cached_list("colleges", move || async move {
-            Client::new(&api_url)
-                .colleges()
-                .send()
-                .await
-                .map_err(|e| e.into())
+            Ok(Client::new(&api_url).colleges().send().await?)
         })
         .await
     }
 
-    pub async fn get_campuses(&mut self, college_id: u32) -> anyhow::Result<Vec<Campus>> {
+    pub async fn get_campuses(&self, college_id: u32) -> Result<Vec<Campus>, ApiError> {
         let api_url = self.config.api_url().to_string();
         let key = format!("campuses_{}", college_id);
         self.get_cached_list(&key, move || async move {
             let client = Client::new(&api_url).with_college(college_id);
-            client.campuses()?.send().await.map_err(|e| e.into())
+            Ok(client.campuses()?.send().await?)
         })
         .await
     }
 
-    pub async fn get_groups(&mut self, campus_id: u32) -> anyhow::Result<Vec<Group>> {
+    pub async fn get_groups(&self, campus_id: u32) -> Result<Vec<Group>, ApiError> {
         let api_url = self.config.api_url().to_string();
         let key = format!("groups_{}", campus_id);
         self.get_cached_list(&key, move || async move {
-            Client::new(&api_url)
-                .groups(campus_id)
-                .send()
-                .await
-                .map_err(|e| e.into())
+            Ok(Client::new(&api_url).groups(campus_id).send().await?)
         })
         .await
     }
 
-    async fn get_cached_list<F, Fut, T>(&mut self, key: &str, fetch: F) -> anyhow::Result<Vec<T>>
+    /// Fetches the campuses of a college and the groups of all of them,
+    /// several campuses at a time. Groups keep the order of their campuses.
+    pub async fn get_all_groups(
+        &self,
+        college_id: u32,
+    ) -> Result<(Vec<Campus>, Vec<Group>), ApiError> {
+        let campuses = self.get_campuses(college_id).await?;
+
+        let permits = Arc::new(Semaphore::new(GROUPS_CONCURRENCY));
+        let mut set = JoinSet::new();
+        for (index, campus) in campuses.iter().enumerate() {
+            let api = self.clone();
+            let permits = permits.clone();
+            let campus_id = campus.id;
+            set.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                (index, api.get_groups(campus_id).await)
+            });
+        }
+
+        let mut lists = Vec::with_capacity(campuses.len());
+        while let Some(joined) = set.join_next().await {
+            let (index, result) = joined.map_err(anyhow::Error::from)?;
+            lists.push((index, result?));
+        }
+        lists.sort_by_key(|(index, _)| *index);
+
+        let groups = lists.into_iter().flat_map(|(_, groups)| groups).collect();
+        Ok((campuses, groups))
+    }
+
+    /// Serves a list from memory or disk while it is younger than
+    /// `lists_cache_ttl`, and otherwise fetches it. When the API cannot be
+    /// reached, the list saved on disk is used however old it is.
+    async fn get_cached_list<F, Fut, T>(&self, key: &str, fetch: F) -> Result<Vec<T>, ApiError>
     where
         F: FnOnce() -> Fut,
-        Fut: std::future::Future<Output = anyhow::Result<Vec<T>>>,
+        Fut: std::future::Future<Output = Result<Vec<T>, ApiError>>,
         T: serde::Serialize + serde::de::DeserializeOwned,
     {
         use std::time::{SystemTime, UNIX_EPOCH};
 
-        if let Some((cached_data, timestamp)) = self.lists_cache.get(key) {
-            let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-            if current_time - timestamp < self.lists_cache_ttl {
-                return Ok(serde_json::from_slice(cached_data)?);
-            }
+        let now = || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+        };
+
+        let cached = self.lists_cache.lock().unwrap().get(key).cloned();
+        if let Some((cached_data, timestamp)) = cached
+            && now().saturating_sub(timestamp) < self.lists_cache_ttl
+        {
+            return Ok(serde_json::from_slice(&cached_data)?);
         }
 
-        let data = fetch().await?;
+        let stored = match &self.cache {
+            Some(cache) => cache.get_list(key).await.unwrap_or_else(|e| {
+                warn!("Failed to read cached {}: {}", key, e);
+                None
+            }),
+            None => None,
+        };
+        if let Some((data, age)) = &stored
+            && *age < self.lists_cache_ttl
+            && let Ok(list) = serde_json::from_slice(data)
+        {
+            debug!("Get {} from disk cache", key);
+            self.remember_list(key, data.clone(), now().saturating_sub(*age));
+            return Ok(list);
+        }
+
+        let data = match fetch().await {
+            Ok(data) => data,
+            Err(e) if e.is_network() => {
+                if let Some((data, age)) = stored
+                    && let Ok(list) = serde_json::from_slice(&data)
+                {
+                    info!("Offline, serving {} cached {}s ago", key, age);
+                    return Ok(list);
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+
         let serialized = serde_json::to_vec(&data)?;
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        self.lists_cache
-            .insert(key.to_string(), (serialized, timestamp));
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.set_list(key, &serialized).await
+        {
+            warn!("Failed to save {} to cache: {}", key, e);
+        }
+        self.remember_list(key, serialized, now());
 
         Ok(data)
     }
 
-    pub async fn fetch(&self, date: &AppDate) -> anyhow::Result<Vec<Schedule>> {
+    fn remember_list(&self, key: &str, data: Vec<u8>, timestamp: u64) {
+        self.lists_cache
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (data, timestamp));
+    }
+
+    pub async fn fetch(&self, date: &AppDate) -> Result<Fetched, ApiError> {
         if let Some(cache) = &self.cache
-            && let Some(data) = cache.get(date).await?
+            && let Some((data, age)) = cache.get(date).await?
         {
             debug!("Get schedule from cache");
-            return Ok(data);
+            return Ok(Fetched::fresh(data, age));
         }
 
-        let group_id = self
-            .group_id
-            .ok_or_else(|| anyhow::anyhow!("Group ID not set"))?;
-
-        let result: Result<Vec<Schedule>, _> = self
-            .client
-            .schedule(group_id)
-            .date(&date.iso())
-            .send()
-            .await;
-
-        let schedules = match result {
-            Ok(s) => s,
-            Err(e) => {
-                let err_str = e.to_string();
-                if err_str.contains("null") || err_str.contains("expected a sequence") {
-                    Vec::new()
-                } else {
-                    return Err(e.into());
+        self.refresh(date).await
+    }
+
+    /// Fetches the schedule from the API, bypassing the cache read but
+    /// still writing the fresh result back to it. When the API cannot be
+    /// reached, an expired cache entry is returned instead.
+    pub async fn refresh(&self, date: &AppDate) -> Result<Fetched, ApiError> {
+        let schedules = match self.request_schedule(date).await {
+            Ok(schedules) => schedules,
+            Err(ApiError::EmptyDay) => Vec::new(),
+            Err(e) if e.is_network() => {
+                if let Some(cache) = &self.cache
+                    && let Ok(Some((schedules, age))) = cache.get_stale(date).await
+                {
+                    info!("Offline, serving {} cached {}s ago", date.iso(), age);
+                    return Ok(Fetched {
+                        schedules,
+                        age,
+                        stale_age: Some(age),
+                    });
                 }
+                return Err(e);
             }
+            Err(e) => return Err(e),
         };
 
         if let Some(cache) = &self.cache {
             let _ = cache.set(date, &schedules).await;
         }
 
-        Ok(schedules)
+        Ok(Fetched::fresh(schedules, 0))
+    }
+
+    /// Fetches Monday–Saturday of the week starting at `start` concurrently.
+    pub async fn fetch_week(&self, start: AppDate) -> Result<Vec<(AppDate, Fetched)>, ApiError> {
+        let mut set = JoinSet::new();
+        for offset in 0..WEEK_DAYS {
+            let api = self.clone();
+            let date = start.add_days(offset);
+            set.spawn(async move { (offset, date, api.fetch(&date).await) });
+        }
+
+        let mut days = Vec::with_capacity(WEEK_DAYS as usize);
+        while let Some(joined) = set.join_next().await {
+            let (offset, date, result) = joined.map_err(anyhow::Error::from)?;
+            days.push((offset, date, result?));
+        }
+        days.sort_by_key(|(offset, _, _)| *offset);
+
+        Ok(days
+            .into_iter()
+            .map(|(_, date, fetched)| (date, fetched))
+            .collect())
+    }
+
+    /// Warms the cache with the days around `center`, as configured by the
+    /// prefetch window, so that day navigation is served locally. Days that
+    /// are already cached and fresh are not requested again.
+    pub async fn prefetch(&self, center: AppDate) {
+        let (before, after) = self.config.prefetch_window();
+        if self.cache.is_none() || (before == 0 && after == 0) {
+            return;
+        }
+
+        let permits = Arc::new(Semaphore::new(self.config.prefetch_concurrency()));
+        let mut set = JoinSet::new();
+        for offset in -i64::from(before)..=i64::from(after) {
+            if offset == 0 {
+                continue;
+            }
+            let api = self.clone();
+            let permits = permits.clone();
+            let date = center.add_days(offset);
+            set.spawn(async move {
+                let Ok(_permit) = permits.acquire_owned().await else {
+                    return;
+                };
+                if let Err(e) = api.fetch(&date).await {
+                    debug!("Prefetch of {} failed: {}", date.iso(), e);
+                }
+            });
+        }
+        while set.join_next().await.is_some() {}
+    }
+
+    async fn request_schedule(&self, date: &AppDate) -> Result<Vec<Schedule>, ApiError> {
+        let group_id = self
+            .group_id
+            .filter(|id| *id != 0)
+            .ok_or(ApiError::MissingGroup)?;
+
+        // The API answers `null` instead of `[]` for days without lessons.
+        let path = format!("/groups/{}/schedules?date={}", group_id, date.iso());
+        self.client
+            .get_json::<Option<Vec<Schedule>>>(&path)
+            .await?
+            .ok_or(ApiError::EmptyDay)
     }
 
-    pub async fn clear_cache(&mut self) -> anyhow::Result<()> {
+    pub async fn clear_cache(&self) -> anyhow::Result<()> {
         if let Some(cache) = &self.cache {
             cache.clear().await?;
+            cache.clear_lists().await?;
         }
-        self.lists_cache.clear();
+        self.lists_cache.lock().unwrap().clear();
         Ok(())
     }
 
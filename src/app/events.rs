@@ -1,75 +1,288 @@
 use crate::{
-    api::ApiClient,
-    app::{App, AppMode},
+    app::{App, AppMode, AppState},
+    config::keymap::Bindings,
+    ui::screens::selector::SelectionStage,
+    utils::AppDate,
 };
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::VecDeque;
 
 pub async fn handle_event(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
     app.state_mut().clear_error_if_expired();
 
+    // Keys held back for a sequence that broke off are queued here to be
+    // handled again, as if they had been typed on their own.
+    let mut keys = VecDeque::from([key]);
+    while let Some(key) = keys.pop_front() {
+        if app.state().goto_input.is_some() {
+            handle_goto_input(app, key).await?;
+            continue;
+        }
+
+        if let Some(input) = resolve_sequence(app, key, &mut keys) {
+            dispatch(app, &input).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn dispatch(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    if app.state().profile_picker.is_some() {
+        return handle_profile_picker(app, input).await;
+    }
+
+    if app.state().group_picker.is_some() {
+        return handle_group_picker(app, input).await;
+    }
+
     match app.state().mode {
-        AppMode::Normal => handle_normal_mode(app, key).await,
-        AppMode::Selector => handle_selector_mode(app, key).await,
-        AppMode::Setup => handle_setup_mode(app, key).await,
+        AppMode::Normal => handle_normal_mode(app, input).await,
+        AppMode::Week => handle_week_mode(app, input).await,
+        AppMode::Calendar => handle_calendar_mode(app, input).await,
+        AppMode::Selector => handle_selector_mode(app, input).await,
+        AppMode::Setup => handle_setup_mode(app, input).await,
     }
 }
 
-async fn handle_normal_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
-    let keymap = app.state().config.keymap().clone();
+/// Holds keys back while they are the start of a sequence binding such as
+/// `g g`, and returns the keys to act on once they are not. When a key
+/// breaks off a sequence, the first held key is returned on its own and
+/// the keys after it are put in front of `replay`.
+fn resolve_sequence(
+    app: &mut App,
+    key: KeyEvent,
+    replay: &mut VecDeque<KeyEvent>,
+) -> Option<Vec<KeyEvent>> {
+    // Keys typed into the selector filter are text, not bindings.
+    if app.state().mode == AppMode::Selector && app.state().filter_focused {
+        return Some(vec![key]);
+    }
+
+    let bindings = active_bindings(app.state());
+    let mut input = std::mem::take(&mut app.state_mut().pending_keys);
+    input.push(key);
+
+    if input.len() > 1
+        && !bindings
+            .iter()
+            .any(|keys| keys.matches(&input) || keys.continues(&input))
+    {
+        let rest = input.split_off(1);
+        for key in rest.into_iter().rev() {
+            replay.push_front(key);
+        }
+        return Some(input);
+    }
+
+    if !bindings.iter().any(|keys| keys.matches(&input))
+        && bindings.iter().any(|keys| keys.continues(&input))
+    {
+        app.state_mut().pending_keys = input;
+        return None;
+    }
+
+    Some(input)
+}
+
+fn active_bindings(state: &AppState) -> Vec<Bindings> {
+    let keymap = state.config.keymap();
+    let actions = if state.profile_picker.is_some() || state.group_picker.is_some() {
+        keymap.selector().actions()
+    } else {
+        match state.mode {
+            AppMode::Normal => keymap.normal().actions(),
+            AppMode::Week => [keymap.week().actions(), keymap.normal().actions()].concat(),
+            AppMode::Calendar => [keymap.calendar().actions(), keymap.normal().actions()].concat(),
+            AppMode::Selector => keymap.selector().actions(),
+            AppMode::Setup => keymap.setup().actions(),
+        }
+    };
+
+    actions.into_iter().map(|(_, keys)| keys).collect()
+}
+
+async fn handle_normal_mode(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
+    let keys = app.state().config.keymap().normal();
 
     match key.code {
-        code if code == keymap.exit() => {
+        _ if keys.exit().matches(input) => {
             app.quit();
         }
-        code if code == keymap.selector() => {
-            let mut api = app.take_api();
-            if let Some(ref mut api) = api {
-                app.state_mut()
-                    .enter_selector(crate::ui::screens::selector::SelectionStage::College);
-                app.state_mut().load_colleges(api).await?;
-            }
-            app.set_api(api.unwrap());
+        _ if keys.selector().matches(input) => {
+            app.state_mut().enter_selector(SelectionStage::College);
+            app.load_colleges();
+        }
+        _ if keys.find_group().matches(input) => {
+            let college_id = app.state().config.college_id();
+            // Stepping back from the search leads to the college list.
+            app.state_mut().enter_selector(SelectionStage::College);
+            app.find_group(college_id);
         }
-        code if code == keymap.settings() => {
+        _ if keys.settings().matches(input) => {
             app.state_mut().enter_setup();
         }
-        code if code == keymap.prev_day() => {
-            app.state_mut().prev_day();
-            let date = app.state().current_date;
-            let schedules = if let Some(api) = app.api() {
-                api.fetch(&date).await?
+        _ if keys.week_view().matches(input) => {
+            app.state_mut().enter_week();
+            app.load_week();
+        }
+        _ if keys.calendar().matches(input) => {
+            app.state_mut().enter_calendar();
+            app.load_month();
+        }
+        _ if keys.goto_date().matches(input) => {
+            app.state_mut().goto_input = Some(String::new());
+        }
+        _ if keys.profiles().matches(input) => {
+            let config = &app.state().config;
+            let active = config
+                .profile_names()
+                .iter()
+                .position(|name| *name == config.active_profile())
+                .unwrap_or(0);
+            app.state_mut().profile_picker = Some(active);
+        }
+        _ if keys.recent().matches(input) => {
+            let config = &app.state().config;
+            let groups = config.saved_groups();
+            if groups.is_empty() {
+                let message = format!(
+                    "No recent groups yet; press {} to pick one.",
+                    crate::ui::screens::normal::format_key(&config.keymap().normal().selector())
+                );
+                app.set_error_message(message);
             } else {
-                Vec::new()
-            };
-            app.state_mut().schedules = schedules;
+                let current = groups
+                    .iter()
+                    .position(|group| config.is_current_group(group))
+                    .unwrap_or(0);
+                app.state_mut().group_picker = Some(current);
+            }
         }
-        code if code == keymap.cur_day() => {
+        _ if keys.prev_day().matches(input) => {
+            app.state_mut().prev_day();
+            app.load_schedules();
+        }
+        _ if keys.cur_day().matches(input) => {
             app.state_mut().go_today();
-            let date = app.state().current_date;
-            let schedules = if let Some(api) = app.api() {
-                api.fetch(&date).await?
-            } else {
-                Vec::new()
-            };
-            app.state_mut().schedules = schedules;
+            app.load_schedules();
         }
-        code if code == keymap.next_day() => {
+        _ if keys.next_day().matches(input) => {
             app.state_mut().next_day();
-            let date = app.state().current_date;
-            let schedules = if let Some(api) = app.api() {
-                api.fetch(&date).await?
-            } else {
-                Vec::new()
-            };
-            app.state_mut().schedules = schedules;
-        }
-        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::SHIFT) => {
-            let date = app.state().current_date;
-            if let Some(api) = app.api_mut() {
-                api.clear_cache().await?;
-                let schedules = api.fetch(&date).await?;
-                app.state_mut().schedules = schedules;
-            }
+            app.load_schedules();
+        }
+        _ if keys.reload().matches(input) => {
+            app.reload_schedules();
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_week_mode(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
+    let keymap = app.state().config.keymap();
+    let keys = keymap.normal();
+    let back = keymap.week().back();
+
+    match key.code {
+        _ if keys.exit().matches(input) => {
+            app.quit();
+        }
+        _ if keys.week_view().matches(input) || back.matches(input) => {
+            app.state_mut().enter_normal();
+            app.load_schedules();
+        }
+        _ if keys.goto_date().matches(input) => {
+            app.state_mut().goto_input = Some(String::new());
+        }
+        _ if keys.prev_day().matches(input) => {
+            app.state_mut().prev_week();
+            app.load_week();
+        }
+        _ if keys.cur_day().matches(input) => {
+            app.state_mut().go_today();
+            app.load_week();
+        }
+        _ if keys.next_day().matches(input) => {
+            app.state_mut().next_week();
+            app.load_week();
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_calendar_mode(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
+    let keymap = app.state().config.keymap().clone();
+    let keys = keymap.calendar();
+    let cursor = app.state().calendar_cursor;
+
+    let moved = match key.code {
+        _ if keymap.normal().exit().matches(input) => {
+            app.quit();
+            return Ok(());
+        }
+        _ if keymap.normal().calendar().matches(input) || keys.back().matches(input) => {
+            app.state_mut().enter_normal();
+            app.load_schedules();
+            return Ok(());
+        }
+        _ if keys.open().matches(input) => {
+            app.state_mut().current_date = cursor;
+            app.state_mut().enter_normal();
+            app.load_schedules();
+            return Ok(());
+        }
+        _ if keys.left().matches(input) => cursor.prev(),
+        _ if keys.right().matches(input) => cursor.next(),
+        _ if keys.up().matches(input) => cursor.add_days(-7),
+        _ if keys.down().matches(input) => cursor.add_days(7),
+        _ if keys.prev_month().matches(input) => cursor.add_months(-1),
+        _ if keys.next_month().matches(input) => cursor.add_months(1),
+        _ => return Ok(()),
+    };
+
+    app.state_mut().calendar_cursor = moved;
+    if !moved.same_month(&cursor) {
+        app.load_month();
+    }
+
+    Ok(())
+}
+
+async fn handle_profile_picker(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
+    let Some(index) = app.state().profile_picker else {
+        return Ok(());
+    };
+    let count = app.state().config.profile_names().len();
+    let keys = app.state().config.keymap().selector();
+
+    match key.code {
+        _ if keys.up().matches(input) => {
+            app.state_mut().profile_picker = Some(index.saturating_sub(1))
+        }
+        _ if keys.down().matches(input) => {
+            app.state_mut().profile_picker = Some((index + 1).min(count - 1))
+        }
+        _ if keys.cancel().matches(input) => app.state_mut().profile_picker = None,
+        _ if keys.select().matches(input) => {
+            app.state_mut().profile_picker = None;
+            let name = app.state().config.profile_names()[index].to_string();
+            app.switch_profile(&name).await?;
         }
         _ => {}
     }
@@ -77,17 +290,163 @@ async fn handle_normal_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()>
     Ok(())
 }
 
-async fn handle_selector_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+async fn handle_group_picker(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
+    let Some(index) = app.state().group_picker else {
+        return Ok(());
+    };
+    let config = &app.state().config;
+    let groups = config.saved_groups();
+    let Some(group) = groups.get(index).map(|g| (*g).clone()) else {
+        app.state_mut().group_picker = None;
+        return Ok(());
+    };
+    let count = groups.len();
+    let keys = config.keymap().selector();
+
     match key.code {
+        _ if keys.up().matches(input) => {
+            app.state_mut().group_picker = Some(index.saturating_sub(1))
+        }
+        _ if keys.down().matches(input) => {
+            app.state_mut().group_picker = Some((index + 1).min(count - 1))
+        }
+        _ if keys.favorite().matches(input) => {
+            let config = &mut app.state_mut().config;
+            config.toggle_favorite(group.clone());
+            config.save().await?;
+            // Starring moves the entry; keep it highlighted.
+            let moved = config
+                .saved_groups()
+                .iter()
+                .position(|g| g.same_group(&group))
+                .unwrap_or(0);
+            app.state_mut().group_picker = Some(moved);
+        }
+        _ if keys.cancel().matches(input) => app.state_mut().group_picker = None,
+        _ if keys.select().matches(input) => {
+            app.state_mut().group_picker = None;
+            app.switch_group(group).await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_goto_input(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+    let Some(input) = app.state_mut().goto_input.as_mut() else {
+        return Ok(());
+    };
+
+    match key.code {
+        KeyCode::Char(c) => input.push(c),
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        KeyCode::Esc => app.state_mut().goto_input = None,
         KeyCode::Enter => {
+            let input = input.clone();
+            match AppDate::parse(&input, AppDate::today()) {
+                Ok(date) => {
+                    app.state_mut().goto_input = None;
+                    app.state_mut().current_date = date;
+                    if app.state().mode == AppMode::Week {
+                        app.load_week();
+                    } else {
+                        app.load_schedules();
+                    }
+                }
+                Err(e) => {
+                    app.set_error_message(format!("Cannot go to '{}': {}", input.trim(), e));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_selector_mode(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
+    let keys = app.state().config.keymap().selector();
+
+    let typed = match key.code {
+        KeyCode::Char(c)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            Some(c)
+        }
+        _ => None,
+    };
+    if app.state().filter_focused
+        && let Some(c) = typed
+    {
+        app.state_mut().push_filter(c);
+        return Ok(());
+    }
+
+    match key.code {
+        _ if keys.select().matches(input) => {
             handle_selector_selection(app).await?;
         }
-        KeyCode::Down => app.state_mut().next_item(),
-        KeyCode::Up => app.state_mut().prev_item(),
-        KeyCode::Right => app.state_mut().next_page(),
-        KeyCode::Left => app.state_mut().prev_page(),
-        KeyCode::Esc => {
-            app.state_mut().enter_normal();
+        _ if keys.down().matches(input) => app.state_mut().next_item(),
+        _ if keys.up().matches(input) => app.state_mut().prev_item(),
+        _ if keys.next_page().matches(input) => app.state_mut().next_page(),
+        _ if keys.prev_page().matches(input) => app.state_mut().prev_page(),
+        _ if keys.first().matches(input) => app.state_mut().first_item(),
+        _ if keys.last().matches(input) => app.state_mut().last_item(),
+        _ if keys.filter().matches(input) => app.state_mut().filter_focused = true,
+        _ if keys.find_group().matches(input) => {
+            let college = match app.state().selection_stage {
+                SelectionStage::College => app.state().get_selected_college().cloned(),
+                SelectionStage::Campus | SelectionStage::Group => {
+                    app.state().selected_college.clone()
+                }
+                SelectionStage::FindGroup => None,
+            };
+            if let Some(college) = college {
+                let college_id = college.college_id;
+                app.state_mut().selected_college = Some(college);
+                app.find_group(college_id);
+            }
+        }
+        _ if keys.favorite().matches(input) => {
+            let state = app.state();
+            if matches!(
+                state.selection_stage,
+                SelectionStage::Group | SelectionStage::FindGroup
+            ) && let Some(group) = state.get_selected_group()
+            {
+                let group = state.saved_group(group);
+                app.state_mut().config.toggle_favorite(group);
+                app.state_mut().config.save().await?;
+            }
+        }
+        _ if keys.cancel().matches(input) => {
+            // The first press only drops the filter.
+            if app.state().filter_focused || !app.state().filter.is_empty() {
+                app.state_mut().clear_filter();
+            } else {
+                app.state_mut().enter_normal();
+            }
+        }
+        KeyCode::Backspace if !app.state().filter.is_empty() => app.state_mut().pop_filter(),
+        _ if keys.back().matches(input) => {
+            app.state_mut().pop_stage();
+            reload_empty_stage(app);
+        }
+        // Characters without a binding start filtering right away.
+        KeyCode::Char(c) if typed.is_some() => {
+            app.state_mut().filter_focused = true;
+            app.state_mut().push_filter(c);
         }
         _ => {}
     }
@@ -96,8 +455,6 @@ async fn handle_selector_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()
 }
 
 async fn handle_selector_selection(app: &mut App) -> anyhow::Result<()> {
-    use crate::ui::screens::selector::SelectionStage;
-
     let stage = app.state().selection_stage;
 
     match stage {
@@ -105,52 +462,25 @@ async fn handle_selector_selection(app: &mut App) -> anyhow::Result<()> {
             if let Some(college) = app.state().get_selected_college().cloned() {
                 let college_id = college.college_id;
                 app.state_mut().selected_college = Some(college);
-                app.state_mut().selection_stage = SelectionStage::Campus;
-
-                let mut api = app.take_api();
-                if let Some(ref mut api) = api {
-                    app.state_mut().load_campuses(api, college_id).await?;
-                }
-                app.set_api(api.unwrap());
+                app.state_mut().push_stage(SelectionStage::Campus);
+                app.state_mut().set_campuses(Vec::new());
+                app.load_campuses(college_id);
             }
         }
         SelectionStage::Campus => {
             if let Some(campus) = app.state().get_selected_campus().cloned() {
                 let campus_id = campus.id;
                 app.state_mut().selected_campus = Some(campus);
-                app.state_mut().selection_stage = SelectionStage::Group;
-
-                let mut api = app.take_api();
-                if let Some(ref mut api) = api {
-                    app.state_mut().load_groups(api, campus_id).await?;
-                }
-                app.set_api(api.unwrap());
+                app.state_mut().push_stage(SelectionStage::Group);
+                app.state_mut().set_groups(Vec::new());
+                app.load_groups(campus_id);
             }
         }
-        SelectionStage::Group => {
-            let group = app.state().get_selected_group().cloned();
-            let college = app.state().selected_college.clone();
-            let campus = app.state().selected_campus.clone();
-
-            if let Some(group) = group {
-                if let Some(ref college) = college {
-                    app.state_mut().config.set_college(college.college_id);
-                }
-                if let Some(ref campus) = campus {
-                    app.state_mut().config.set_campus(campus.id);
-                }
-                app.state_mut().config.set_group(group.id);
-                app.state_mut().config.save().await?;
-
-                let new_api = ApiClient::new(app.state().config.clone()).await?;
-                app.set_api(new_api);
-
-                let mut schedules = Vec::new();
-                if let Some(api) = app.api() {
-                    schedules = api.fetch(&app.state().current_date).await?;
-                }
-                app.state_mut().schedules = schedules;
-                app.state_mut().enter_normal();
+        SelectionStage::Group | SelectionStage::FindGroup => {
+            let state = app.state();
+            if let Some(group) = state.get_selected_group() {
+                let group = state.saved_group(group);
+                app.switch_group(group).await?;
             }
         }
     }
@@ -158,11 +488,42 @@ async fn handle_selector_selection(app: &mut App) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn handle_setup_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
+/// Loads the list of a stage stepped back to when it was never loaded,
+/// e.g. the colleges after opening the group search directly, or the
+/// groups of a campus left for the search before they arrived.
+fn reload_empty_stage(app: &mut App) {
+    let state = app.state();
+    if state.is_loading() {
+        return;
+    }
+    match state.selection_stage {
+        SelectionStage::College if state.colleges.is_empty() => app.load_colleges(),
+        SelectionStage::Campus if state.campuses.is_empty() => {
+            if let Some(college_id) = state.selected_college.as_ref().map(|c| c.college_id) {
+                app.load_campuses(college_id);
+            }
+        }
+        SelectionStage::Group if state.groups.is_empty() => {
+            if let Some(campus_id) = state.selected_campus.as_ref().map(|c| c.id) {
+                app.load_groups(campus_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn handle_setup_mode(app: &mut App, input: &[KeyEvent]) -> anyhow::Result<()> {
+    let Some(&key) = input.last() else {
+        return Ok(());
+    };
     use crate::app::state::SetupField;
 
+    let keys = app.state().config.keymap().setup();
+
+    // Bindings are checked before text input, so a printable key bound
+    // here cannot be typed into the fields.
     match key.code {
-        KeyCode::Enter => {
+        _ if keys.save().matches(input) => {
             let state = app.state();
             let url = state.setup_api_url.trim().to_string();
             let college_id = state.setup_college_id.parse().unwrap_or(1);
@@ -174,10 +535,10 @@ async fn handle_setup_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
             app.state_mut().config.set_group(0);
 
             app.state_mut().config.save().await?;
-            app.reload_api().await?;
             app.state_mut().enter_normal();
+            app.reload_api().await?;
         }
-        KeyCode::Tab => {
+        _ if keys.next_field().matches(input) => {
             app.state_mut().setup_field = match app.state().setup_field {
                 SetupField::ApiUrl => SetupField::CollegeId,
                 SetupField::CollegeId => SetupField::CampusId,
@@ -185,7 +546,7 @@ async fn handle_setup_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
                 SetupField::GroupId => SetupField::ApiUrl,
             };
         }
-        KeyCode::BackTab => {
+        _ if keys.prev_field().matches(input) => {
             app.state_mut().setup_field = match app.state().setup_field {
                 SetupField::ApiUrl => SetupField::GroupId,
                 SetupField::CollegeId => SetupField::ApiUrl,
@@ -193,6 +554,9 @@ async fn handle_setup_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
                 SetupField::GroupId => SetupField::CampusId,
             };
         }
+        _ if keys.cancel().matches(input) => {
+            app.state_mut().enter_normal();
+        }
         KeyCode::Char(c) => match app.state().setup_field {
             SetupField::ApiUrl => app.state_mut().setup_api_url.push(c),
             SetupField::CollegeId => app.state_mut().setup_college_id.push(c),
@@ -213,9 +577,6 @@ async fn handle_setup_mode(app: &mut App, key: KeyEvent) -> anyhow::Result<()> {
                 app.state_mut().setup_group_id.pop();
             }
         },
-        KeyCode::Esc => {
-            app.state_mut().enter_normal();
-        }
         _ => {}
     }
 
@@ -1,46 +1,481 @@
 pub mod events;
 pub mod state;
+pub mod tasks;
 
-use crate::{api::ApiClient, config::Config};
+use crate::{
+    api::{ApiClient, ApiError},
+    config::{Config, SavedGroup},
+    ui::screens::selector::SelectionStage,
+    utils::AppDate,
+};
 use crossterm::event::KeyEvent;
 pub use state::{AppMode, AppState, SetupField};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+pub use tasks::TaskResult;
+use tokio::{
+    sync::{Semaphore, mpsc},
+    task::AbortHandle,
+};
+
+/// How many days of a calendar month are fetched at the same time.
+const MONTH_CONCURRENCY: usize = 4;
 
 pub struct App {
-    pub config: Config,
     pub state: AppState,
     api: Option<ApiClient>,
     should_quit: bool,
+    tasks_tx: mpsc::UnboundedSender<TaskResult>,
+    tasks_rx: mpsc::UnboundedReceiver<TaskResult>,
+    refresh_started: Instant,
+    refresh_in_flight: bool,
+    // Only the window around the latest date is worth warming
+    prefetch: Option<AbortHandle>,
+    // Bumped whenever the API client is replaced or reconfigured
+    generation: u64,
 }
 
 impl App {
     pub async fn new(config: Config) -> anyhow::Result<Self> {
-        let mut state = AppState::new(config.clone());
+        let state = AppState::new(config.clone());
 
         match ApiClient::new(config.clone()).await {
             Ok(api) => {
-                state.load_schedules(&api).await?;
-                Ok(Self {
-                    config,
-                    state,
-                    api: Some(api),
-                    should_quit: false,
-                })
+                let mut app = Self::with_parts(state, api);
+                app.load_schedules();
+                Ok(app)
             }
             Err(e) => {
                 log::warn!("Failed to initialize API client: {}", e);
-                let mut api = ApiClient::new_base(config.clone()).await?;
-                state.enter_selector(crate::ui::screens::selector::SelectionStage::College);
-                state.load_colleges(&mut api).await?;
-                Ok(Self {
-                    config,
-                    state,
-                    api: Some(api),
-                    should_quit: false,
-                })
+                let api = ApiClient::new_base(config).await?;
+                let mut app = Self::with_parts(state, api);
+                app.state.enter_selector(SelectionStage::College);
+                app.load_colleges();
+                Ok(app)
             }
         }
     }
 
+    fn with_parts(state: AppState, api: ApiClient) -> Self {
+        let (tasks_tx, tasks_rx) = mpsc::unbounded_channel();
+        Self {
+            state,
+            api: Some(api),
+            should_quit: false,
+            tasks_tx,
+            tasks_rx,
+            refresh_started: Instant::now(),
+            refresh_in_flight: false,
+            prefetch: None,
+            generation: 0,
+        }
+    }
+
+    /// Drives work that must not block the UI: applies results of finished
+    /// tasks and starts a background refresh every `refresh_interval`.
+    pub fn tick(&mut self) {
+        while let Ok(result) = self.tasks_rx.try_recv() {
+            self.apply_task_result(result);
+        }
+
+        let interval = self.state.config.refresh_interval();
+        if interval == 0
+            || self.refresh_in_flight
+            || self.state.mode != AppMode::Normal
+            || self.refresh_started.elapsed() < Duration::from_secs(interval)
+        {
+            return;
+        }
+
+        if let Some(api) = self.api.clone() {
+            let generation = self.generation;
+            let date = self.state.current_date;
+            self.refresh_started = Instant::now();
+            self.refresh_in_flight = true;
+            self.spawn(async move {
+                TaskResult::Schedules {
+                    generation,
+                    date,
+                    background: true,
+                    result: api.refresh(&date).await,
+                }
+            });
+        }
+    }
+
+    fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = TaskResult> + Send + 'static,
+    {
+        let tx = self.tasks_tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(task.await);
+        });
+    }
+
+    fn apply_task_result(&mut self, result: TaskResult) {
+        match result {
+            TaskResult::Schedules {
+                generation,
+                date,
+                background,
+                result,
+            } => {
+                if background {
+                    self.refresh_in_flight = false;
+                }
+                if generation != self.generation {
+                    log::debug!(
+                        "Discarding schedule for {} from a replaced client",
+                        date.iso()
+                    );
+                    return;
+                }
+                if date != self.state.current_date {
+                    log::debug!("Discarding stale schedule for {}", date.iso());
+                    return;
+                }
+                // The spinner of another view belongs to that view's own
+                // request, which this one does not finish.
+                if !background && self.state.mode == AppMode::Normal {
+                    self.state.finish_loading();
+                }
+                match result {
+                    Ok(fetched) => {
+                        self.state.offline = fetched.stale_age.is_some();
+                        self.state.stale_age = fetched.stale_age;
+                        self.state.record_lessons(date, &fetched.schedules);
+                        self.state.set_schedules(fetched.schedules, fetched.age);
+                        if !background && !self.state.offline {
+                            self.prefetch_around(date);
+                        }
+                    }
+                    Err(e) if background => {
+                        log::warn!("Background refresh failed: {}", e);
+                        self.state.offline = e.is_network();
+                    }
+                    Err(e) => self.report_api_error(e),
+                }
+            }
+            TaskResult::Week {
+                generation,
+                start,
+                result,
+            } => {
+                if generation != self.generation
+                    || self.state.mode != AppMode::Week
+                    || start != self.state.current_date.week_start()
+                {
+                    log::debug!("Discarding stale week starting {}", start.iso());
+                    return;
+                }
+                self.state.finish_loading();
+                match result {
+                    Ok(week) => {
+                        let stale_age = week.iter().filter_map(|(_, f)| f.stale_age).max();
+                        let age = week.iter().map(|(_, f)| f.age).max().unwrap_or_default();
+                        self.state.offline = stale_age.is_some();
+                        self.state.stale_age = stale_age;
+                        for (date, fetched) in &week {
+                            self.state.record_lessons(*date, &fetched.schedules);
+                        }
+                        self.state.set_week(
+                            week.into_iter()
+                                .map(|(date, fetched)| (date, fetched.schedules))
+                                .collect(),
+                            age,
+                        );
+                    }
+                    Err(e) => self.report_api_error(e),
+                }
+            }
+            TaskResult::MonthDay {
+                generation,
+                date,
+                result,
+            } => {
+                if generation != self.generation || !self.state.calendar_pending.remove(&date) {
+                    return;
+                }
+                match result {
+                    Ok(schedules) => self.state.record_lessons(date, &schedules),
+                    Err(e) => log::warn!("Failed to load {} for calendar: {}", date.iso(), e),
+                }
+            }
+            TaskResult::Colleges(result) => {
+                if self.state.mode != AppMode::Selector
+                    || self.state.selection_stage != SelectionStage::College
+                {
+                    return;
+                }
+                self.state.finish_loading();
+                match result {
+                    Ok(colleges) => self.state.set_colleges(colleges),
+                    Err(e) => self.report_api_error(e),
+                }
+            }
+            TaskResult::Campuses { college_id, result } => {
+                let current = self.state.selected_college.as_ref().map(|c| c.college_id);
+                if self.state.mode != AppMode::Selector
+                    || self.state.selection_stage != SelectionStage::Campus
+                    || current != Some(college_id)
+                {
+                    return;
+                }
+                self.state.finish_loading();
+                match result {
+                    Ok(campuses) => self.state.set_campuses(campuses),
+                    Err(e) => self.report_api_error(e),
+                }
+            }
+            TaskResult::Groups { campus_id, result } => {
+                let current = self.state.selected_campus.as_ref().map(|c| c.id);
+                if self.state.mode != AppMode::Selector
+                    || self.state.selection_stage != SelectionStage::Group
+                    || current != Some(campus_id)
+                {
+                    return;
+                }
+                self.state.finish_loading();
+                match result {
+                    Ok(groups) => self.state.set_groups(groups),
+                    Err(e) => self.report_api_error(e),
+                }
+            }
+            TaskResult::AllGroups { college_id, result } => {
+                if self.state.mode != AppMode::Selector
+                    || self.state.selection_stage != SelectionStage::FindGroup
+                    || self.state.find_college_id != Some(college_id)
+                {
+                    return;
+                }
+                self.state.finish_loading();
+                match result {
+                    Ok((campuses, groups)) => {
+                        self.state.campuses = campuses;
+                        self.state.set_groups(groups);
+                    }
+                    Err(e) => self.report_api_error(e),
+                }
+            }
+        }
+    }
+
+    /// Shows a failed foreground request to the user in a way that fits
+    /// the kind of failure.
+    fn report_api_error(&mut self, error: ApiError) {
+        log::error!("API request failed: {}", error);
+        match error {
+            ApiError::Offline(_) | ApiError::Timeout => {
+                self.state.offline = true;
+                self.set_error_message(format!(
+                    "{}. Check your connection; the last loaded data stays on screen.",
+                    error
+                ));
+            }
+            ApiError::MissingGroup => {
+                self.state.enter_selector(SelectionStage::College);
+                self.load_colleges();
+                self.set_error_message("Select your group to see the schedule".to_string());
+            }
+            ApiError::Http { status: 404, .. } if self.state.mode != AppMode::Selector => {
+                self.set_error_message(format!(
+                    "{}. The configured group may not exist on this server; press {} to pick another.",
+                    error,
+                    crate::ui::screens::normal::format_key(
+                        &self.state.config.keymap().normal().selector()
+                    )
+                ));
+            }
+            ApiError::Decode(_) => {
+                self.set_error_message(format!(
+                    "{}. The API URL may point to an incompatible server.",
+                    error
+                ));
+            }
+            _ => self.set_error_message(format!("Error: {}", error)),
+        }
+    }
+
+    /// Starts fetching the schedule for the current date.
+    pub fn load_schedules(&mut self) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        let generation = self.generation;
+        let date = self.state.current_date;
+        self.state.start_loading();
+        self.spawn(async move {
+            TaskResult::Schedules {
+                generation,
+                date,
+                background: false,
+                result: api.fetch(&date).await,
+            }
+        });
+    }
+
+    /// Warms the cache around `date` in the background, cancelling the
+    /// prefetch of the previously loaded date.
+    fn prefetch_around(&mut self, date: AppDate) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        self.cancel_prefetch();
+        let handle = tokio::spawn(async move { api.prefetch(date).await });
+        self.prefetch = Some(handle.abort_handle());
+    }
+
+    /// Stops warming the cache, e.g. because the group it was warming for
+    /// is no longer selected.
+    fn cancel_prefetch(&mut self) {
+        if let Some(prefetch) = self.prefetch.take() {
+            prefetch.abort();
+        }
+    }
+
+    /// Starts fetching Monday–Saturday of the current date's week.
+    pub fn load_week(&mut self) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        let generation = self.generation;
+        let start = self.state.current_date.week_start();
+        self.state.start_loading();
+        self.spawn(async move {
+            TaskResult::Week {
+                generation,
+                start,
+                result: api.fetch_week(start).await,
+            }
+        });
+    }
+
+    /// Lazily fetches the days of the calendar cursor's month whose lesson
+    /// count is not known yet.
+    pub fn load_month(&mut self) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        let dates: Vec<AppDate> = self
+            .state
+            .calendar_cursor
+            .month_days()
+            .into_iter()
+            .filter(|date| {
+                !self.state.lesson_counts.contains_key(date)
+                    && !self.state.calendar_pending.contains(date)
+            })
+            .collect();
+        if dates.is_empty() {
+            return;
+        }
+        self.state.calendar_pending.extend(dates.iter().copied());
+
+        let generation = self.generation;
+        let tx = self.tasks_tx.clone();
+        tokio::spawn(async move {
+            let permits = Arc::new(Semaphore::new(MONTH_CONCURRENCY));
+            for date in dates {
+                let Ok(permit) = permits.clone().acquire_owned().await else {
+                    break;
+                };
+                let api = api.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let result = api.fetch(&date).await.map(|f| f.schedules);
+                    drop(permit);
+                    let _ = tx.send(TaskResult::MonthDay {
+                        generation,
+                        date,
+                        result,
+                    });
+                });
+            }
+        });
+    }
+
+    /// Drops every cached entry and re-fetches the current date.
+    pub fn reload_schedules(&mut self) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        let generation = self.generation;
+        let date = self.state.current_date;
+        self.state.reset_lesson_counts();
+        self.state.start_loading();
+        self.spawn(async move {
+            let result = match api.clear_cache().await {
+                Ok(()) => api.refresh(&date).await,
+                Err(e) => Err(e.into()),
+            };
+            TaskResult::Schedules {
+                generation,
+                date,
+                background: false,
+                result,
+            }
+        });
+    }
+
+    pub fn load_colleges(&mut self) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        self.state.start_loading();
+        self.spawn(async move { TaskResult::Colleges(api.get_colleges().await) });
+    }
+
+    pub fn load_campuses(&mut self, college_id: u32) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        self.state.start_loading();
+        self.spawn(async move {
+            TaskResult::Campuses {
+                college_id,
+                result: api.get_campuses(college_id).await,
+            }
+        });
+    }
+
+    pub fn load_groups(&mut self, campus_id: u32) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        self.state.start_loading();
+        self.spawn(async move {
+            TaskResult::Groups {
+                campus_id,
+                result: api.get_groups(campus_id).await,
+            }
+        });
+    }
+
+    /// Opens the group search over every campus of `college_id`.
+    pub fn find_group(&mut self, college_id: u32) {
+        self.state.push_stage(SelectionStage::FindGroup);
+        self.state.find_college_id = Some(college_id);
+        self.state.set_groups(Vec::new());
+        self.load_all_groups(college_id);
+    }
+
+    pub fn load_all_groups(&mut self, college_id: u32) {
+        let Some(api) = self.api.clone() else {
+            return;
+        };
+        self.state.start_loading();
+        self.spawn(async move {
+            TaskResult::AllGroups {
+                college_id,
+                result: api.get_all_groups(college_id).await,
+            }
+        });
+    }
+
     pub async fn handle_key_event(&mut self, key: KeyEvent) -> anyhow::Result<()> {
         use crate::app::events::handle_event;
         handle_event(self, key).await
@@ -75,20 +510,69 @@ impl App {
     }
 
     pub fn set_api(&mut self, api: ApiClient) {
+        self.cancel_prefetch();
         self.api = Some(api);
+        self.generation += 1;
+        self.state.reset_lesson_counts();
     }
 
     pub fn set_error_message(&mut self, msg: String) {
         self.state.set_error_message(msg);
     }
 
+    /// Switches to the profile `name`, remembering the date shown in the
+    /// current profile and reopening the one last shown in `name`.
+    pub async fn switch_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        if name == self.state.config.active_profile() {
+            return Ok(());
+        }
+
+        let shown = self.state.current_date;
+        self.state.config.set_last_date(shown.into());
+        self.state.config.set_active_profile(name)?;
+        self.state.current_date = self
+            .state
+            .config
+            .last_date()
+            .map(AppDate::from)
+            .unwrap_or_else(AppDate::today);
+        self.state.config.save().await?;
+
+        self.state.enter_normal();
+        self.reload_api().await
+    }
+
+    /// Makes `group` the group of the active profile, records it as the
+    /// most recent one and shows its schedule.
+    pub async fn switch_group(&mut self, group: SavedGroup) -> anyhow::Result<()> {
+        let config = &mut self.state.config;
+        if group.url != config.api_url() {
+            config.set_api_url(group.url.clone());
+        }
+        config.set_college(group.college_id);
+        config.set_campus(group.campus_id);
+        config.set_group(group.group_id);
+        config.push_recent(group);
+        config.save().await?;
+
+        let new_api = ApiClient::new(self.state.config.clone()).await?;
+        self.set_api(new_api);
+
+        self.state.enter_normal();
+        self.load_schedules();
+        Ok(())
+    }
+
     pub async fn reload_api(&mut self) -> anyhow::Result<()> {
+        self.cancel_prefetch();
+        self.generation += 1;
+        self.state.reset_lesson_counts();
         if let Some(api) = &mut self.api {
-            api.reconfigure(&self.config).await?;
-            self.state.load_schedules(api).await?;
+            api.reconfigure(&self.state.config).await?;
         } else {
-            self.api = Some(ApiClient::new(self.config.clone()).await?);
+            self.api = Some(ApiClient::new(self.state.config.clone()).await?);
         }
+        self.load_schedules();
         Ok(())
     }
 }
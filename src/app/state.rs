@@ -1,9 +1,18 @@
-use crate::{api::ApiClient, config::Config, utils::AppDate};
+use crate::{
+    config::{Config, SavedGroup},
+    ui::screens::selector::SelectionStage,
+    utils::{AppDate, clock::clock},
+};
+use chrono::{Duration, NaiveDateTime};
+use crossterm::event::KeyEvent;
 use osars::models::{Campus, College, Group, Schedule};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppMode {
     Normal,
+    Week,
+    Calendar,
     Selector,
     Setup,
 }
@@ -15,9 +24,22 @@ pub struct AppState {
     // Normal mode state
     pub current_date: AppDate,
     pub schedules: Vec<Schedule>,
+    pub last_refresh: Option<NaiveDateTime>,
+    // Set when the last request could not reach the server
+    pub offline: bool,
+    // Age in seconds of the expired cache entry being shown while offline
+    pub stale_age: Option<u64>,
+
+    // Week mode state
+    pub week: Vec<(AppDate, Vec<Schedule>)>,
+
+    // Calendar mode state
+    pub calendar_cursor: AppDate,
+    pub lesson_counts: HashMap<AppDate, usize>,
+    pub calendar_pending: HashSet<AppDate>,
 
     // Selector mode state
-    pub selection_stage: crate::ui::screens::selector::SelectionStage,
+    pub selection_stage: SelectionStage,
     pub colleges: Vec<College>,
     pub campuses: Vec<Campus>,
     pub groups: Vec<Group>,
@@ -26,6 +48,17 @@ pub struct AppState {
     pub page_size: usize,
     pub selected_college: Option<College>,
     pub selected_campus: Option<Campus>,
+    // College whose groups are listed by the group search
+    pub find_college_id: Option<u32>,
+    // Earlier stages to step back to, the latest last
+    pub selector_history: Vec<SelectorFrame>,
+    // Text the list is fuzzy-filtered by
+    pub filter: String,
+    // Whether typed characters go to the filter instead of key bindings
+    pub filter_focused: bool,
+    // Indices into the list of the current stage that match `filter`,
+    // best first; pages and the selection refer to this list
+    pub filtered: Vec<usize>,
 
     // Setup mode state
     pub setup_field: SetupField,
@@ -34,11 +67,35 @@ pub struct AppState {
     pub setup_campus_id: String,
     pub setup_group_id: String,
 
+    // Started when a foreground request is spawned, cleared by its result
+    pub loading: Option<std::time::Instant>,
+
+    // "Go to date" popup, open while `Some`
+    pub goto_input: Option<String>,
+    // Highlighted entry of the profile switcher, when it is open
+    pub profile_picker: Option<usize>,
+    // Highlighted entry of the recent and favorite groups switcher, when
+    // it is open
+    pub group_picker: Option<usize>,
+    // Keys typed so far of a sequence binding such as `g g`
+    pub pending_keys: Vec<KeyEvent>,
+
     // Error display
     pub error_message: Option<String>,
     pub error_timeout: Option<std::time::Instant>,
 }
 
+/// A selector stage as it was left for the next one.
+#[derive(Debug, Clone)]
+pub struct SelectorFrame {
+    pub stage: SelectionStage,
+    pub page: usize,
+    pub selected_index: usize,
+    pub filter: String,
+    // Groups listed by a Group stage, which the group search replaces
+    pub groups: Option<Vec<Group>>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SetupField {
     ApiUrl,
@@ -54,7 +111,14 @@ impl AppState {
             config,
             current_date: AppDate::today(),
             schedules: Vec::new(),
-            selection_stage: crate::ui::screens::selector::SelectionStage::College,
+            last_refresh: None,
+            offline: false,
+            stale_age: None,
+            week: Vec::new(),
+            calendar_cursor: AppDate::today(),
+            lesson_counts: HashMap::new(),
+            calendar_pending: HashSet::new(),
+            selection_stage: SelectionStage::College,
             colleges: Vec::new(),
             campuses: Vec::new(),
             groups: Vec::new(),
@@ -63,53 +127,248 @@ impl AppState {
             page_size: 20,
             selected_college: None,
             selected_campus: None,
+            find_college_id: None,
+            selector_history: Vec::new(),
+            filter: String::new(),
+            filter_focused: false,
+            filtered: Vec::new(),
             setup_field: SetupField::ApiUrl,
             setup_api_url: String::new(),
             setup_college_id: String::new(),
             setup_campus_id: String::new(),
             setup_group_id: String::new(),
+            loading: None,
+            goto_input: None,
+            profile_picker: None,
+            group_picker: None,
+            pending_keys: Vec::new(),
             error_message: None,
             error_timeout: None,
         }
     }
 
-    pub async fn load_schedules(&mut self, api: &ApiClient) -> anyhow::Result<()> {
-        self.schedules = api.fetch(&self.current_date).await?;
-        Ok(())
+    /// Shows `schedules`, which were received from the API `age` seconds
+    /// ago.
+    pub fn set_schedules(&mut self, schedules: Vec<Schedule>, age: u64) {
+        self.schedules = schedules;
+        self.set_refreshed(age);
     }
 
-    pub async fn load_colleges(&mut self, api: &mut ApiClient) -> anyhow::Result<()> {
-        self.colleges = api.get_colleges().await?;
-        self.selected_index = 0;
-        self.page = 0;
-        Ok(())
+    /// Shows `week`, whose oldest day was received `age` seconds ago.
+    pub fn set_week(&mut self, week: Vec<(AppDate, Vec<Schedule>)>, age: u64) {
+        self.week = week;
+        self.set_refreshed(age);
     }
 
-    pub async fn load_campuses(
-        &mut self,
-        api: &mut ApiClient,
-        college_id: u32,
-    ) -> anyhow::Result<()> {
-        self.campuses = api.get_campuses(college_id).await?;
-        self.selected_index = 0;
-        self.page = 0;
-        Ok(())
+    fn set_refreshed(&mut self, age: u64) {
+        self.last_refresh = i64::try_from(age)
+            .ok()
+            .and_then(Duration::try_seconds)
+            .and_then(|age| clock().now().checked_sub_signed(age));
+    }
+
+    pub fn record_lessons(&mut self, date: AppDate, schedules: &[Schedule]) {
+        let count = schedules.iter().map(|s| s.lessons.len()).sum();
+        self.lesson_counts.insert(date, count);
+    }
+
+    /// Forgets lesson counts after the schedule source changed; results of
+    /// month fetches still in flight are ignored.
+    pub fn reset_lesson_counts(&mut self) {
+        self.lesson_counts.clear();
+        self.calendar_pending.clear();
+    }
+
+    pub fn set_colleges(&mut self, colleges: Vec<College>) {
+        self.colleges = colleges;
+        self.refilter();
+        self.select_active();
+    }
+
+    pub fn set_campuses(&mut self, campuses: Vec<Campus>) {
+        self.campuses = campuses;
+        self.refilter();
+        self.select_active();
+    }
+
+    pub fn set_groups(&mut self, groups: Vec<Group>) {
+        self.groups = groups;
+        self.refilter();
+        self.select_active();
+    }
+
+    /// Id of the configured college, campus or group, whichever the
+    /// current stage lists.
+    pub fn active_id(&self) -> u32 {
+        match self.selection_stage {
+            SelectionStage::College => self.config.college_id(),
+            SelectionStage::Campus => self.config.campus_id(),
+            SelectionStage::Group | SelectionStage::FindGroup => self.config.group_id(),
+        }
+    }
+
+    /// `group` of the current list as an entry of the quick switcher, with
+    /// the college and campus it was listed under.
+    pub fn saved_group(&self, group: &Group) -> SavedGroup {
+        let college_id = match self.selection_stage {
+            SelectionStage::FindGroup => self.find_college_id,
+            _ => self.selected_college.as_ref().map(|c| c.college_id),
+        }
+        .unwrap_or_else(|| self.config.college_id());
+        let college = self
+            .selected_college
+            .iter()
+            .chain(&self.colleges)
+            .find(|c| c.college_id == college_id)
+            .map(|c| c.name.clone());
+        let campus = self
+            .selected_campus
+            .iter()
+            .chain(&self.campuses)
+            .find(|c| c.id == group.campus_id)
+            .map(|c| c.name.clone());
+
+        SavedGroup {
+            url: self.config.api_url().to_string(),
+            college_id,
+            campus_id: group.campus_id,
+            group_id: group.id,
+            group: group.name.clone(),
+            campus: campus.unwrap_or_default(),
+            college: college.unwrap_or_default(),
+        }
+    }
+
+    /// Id of item `index` of the current stage's list.
+    pub fn item_id(&self, index: usize) -> Option<u32> {
+        match self.selection_stage {
+            SelectionStage::College => self.colleges.get(index).map(|c| c.college_id),
+            SelectionStage::Campus => self.campuses.get(index).map(|c| c.id),
+            SelectionStage::Group | SelectionStage::FindGroup => {
+                self.groups.get(index).map(|g| g.id)
+            }
+        }
+    }
+
+    /// Moves the selection, and the page, to the configured entry when it
+    /// is in the list.
+    pub fn select_active(&mut self) {
+        let active = self.active_id();
+        if let Some(position) = self
+            .filtered
+            .iter()
+            .position(|&i| self.item_id(i) == Some(active))
+        {
+            self.page = position / self.page_size;
+            self.selected_index = position % self.page_size;
+        }
+    }
+
+    pub fn push_filter(&mut self, c: char) {
+        self.filter.push(c);
+        self.refilter();
+    }
+
+    pub fn pop_filter(&mut self) {
+        self.filter.pop();
+        self.refilter();
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_focused = false;
+        self.refilter();
     }
 
-    pub async fn load_groups(&mut self, api: &mut ApiClient, campus_id: u32) -> anyhow::Result<()> {
-        self.groups = api.get_groups(campus_id).await?;
+    /// Matches the list of the current stage against the filter and moves
+    /// the selection to the best match.
+    pub fn refilter(&mut self) {
+        use crate::utils::fuzzy;
+
+        self.filtered = match self.selection_stage {
+            SelectionStage::College => fuzzy::filter(&self.filter, &self.colleges, |c| &c.name),
+            SelectionStage::Campus => fuzzy::filter(&self.filter, &self.campuses, |c| &c.name),
+            SelectionStage::Group | SelectionStage::FindGroup => {
+                fuzzy::filter(&self.filter, &self.groups, |g| &g.name)
+            }
+        };
         self.selected_index = 0;
         self.page = 0;
-        Ok(())
     }
 
-    pub fn enter_selector(&mut self, stage: crate::ui::screens::selector::SelectionStage) {
+    /// Indices of the items on the current page.
+    pub fn page_items(&self) -> &[usize] {
+        let start = (self.page * self.page_size).min(self.filtered.len());
+        let end = (start + self.page_size).min(self.filtered.len());
+        &self.filtered[start..end]
+    }
+
+    pub fn start_loading(&mut self) {
+        self.loading = Some(std::time::Instant::now());
+    }
+
+    pub fn finish_loading(&mut self) {
+        self.loading = None;
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn enter_selector(&mut self, stage: SelectionStage) {
         self.mode = AppMode::Selector;
         self.selection_stage = stage;
         self.selected_index = 0;
         self.page = 0;
         self.selected_college = None;
         self.selected_campus = None;
+        self.find_college_id = None;
+        self.selector_history.clear();
+        self.clear_filter();
+    }
+
+    /// Moves on to `stage`, remembering the current one for `pop_stage`.
+    pub fn push_stage(&mut self, stage: SelectionStage) {
+        self.selector_history.push(SelectorFrame {
+            stage: self.selection_stage,
+            page: self.page,
+            selected_index: self.selected_index,
+            filter: self.filter.clone(),
+            groups: (self.selection_stage == SelectionStage::Group).then(|| self.groups.clone()),
+        });
+        self.selection_stage = stage;
+        self.clear_filter();
+    }
+
+    /// Returns to the previous stage with its list, page, selection and
+    /// filter.
+    /// Returns `false` when there is no stage to go back to.
+    pub fn pop_stage(&mut self) -> bool {
+        let Some(frame) = self.selector_history.pop() else {
+            return false;
+        };
+
+        match frame.stage {
+            SelectionStage::College => {
+                self.selected_college = None;
+                self.selected_campus = None;
+            }
+            SelectionStage::Campus => self.selected_campus = None,
+            SelectionStage::Group | SelectionStage::FindGroup => {}
+        }
+        // A list still loading for the stage being left is not waited for.
+        self.loading = None;
+
+        if let Some(groups) = frame.groups {
+            self.groups = groups;
+        }
+        self.selection_stage = frame.stage;
+        self.filter = frame.filter;
+        self.filter_focused = false;
+        self.refilter();
+        self.page = frame.page;
+        self.selected_index = frame.selected_index;
+        true
     }
 
     pub fn enter_setup(&mut self) {
@@ -121,8 +380,19 @@ impl AppState {
         self.setup_group_id = self.config.group_id().to_string();
     }
 
+    pub fn enter_week(&mut self) {
+        self.mode = AppMode::Week;
+        self.week.clear();
+    }
+
+    pub fn enter_calendar(&mut self) {
+        self.mode = AppMode::Calendar;
+        self.calendar_cursor = self.current_date;
+    }
+
     pub fn enter_normal(&mut self) {
         self.mode = AppMode::Normal;
+        self.loading = None;
         self.error_message = None;
     }
 
@@ -172,6 +442,20 @@ impl AppState {
         }
     }
 
+    pub fn first_item(&mut self) {
+        self.page = 0;
+        self.selected_index = 0;
+    }
+
+    pub fn last_item(&mut self) {
+        let count = self.current_items_count();
+        if count == 0 {
+            return;
+        }
+        self.page = (count - 1) / self.page_size;
+        self.selected_index = (count - 1) % self.page_size;
+    }
+
     pub fn next_page(&mut self) {
         let count = self.current_items_count();
         let total_pages = count.div_ceil(self.page_size);
@@ -189,27 +473,25 @@ impl AppState {
     }
 
     fn current_items_count(&self) -> usize {
-        use crate::ui::screens::selector::SelectionStage;
-        match self.selection_stage {
-            SelectionStage::College => self.colleges.len(),
-            SelectionStage::Campus => self.campuses.len(),
-            SelectionStage::Group => self.groups.len(),
-        }
+        self.filtered.len()
     }
 
-    pub fn get_selected_college(&self) -> Option<&College> {
+    /// Index into the list of the current stage of the selected item.
+    fn selected_item(&self) -> Option<usize> {
         let idx = self.page * self.page_size + self.selected_index;
-        self.colleges.get(idx)
+        self.filtered.get(idx).copied()
+    }
+
+    pub fn get_selected_college(&self) -> Option<&College> {
+        self.colleges.get(self.selected_item()?)
     }
 
     pub fn get_selected_campus(&self) -> Option<&Campus> {
-        let idx = self.page * self.page_size + self.selected_index;
-        self.campuses.get(idx)
+        self.campuses.get(self.selected_item()?)
     }
 
     pub fn get_selected_group(&self) -> Option<&Group> {
-        let idx = self.page * self.page_size + self.selected_index;
-        self.groups.get(idx)
+        self.groups.get(self.selected_item()?)
     }
 
     // Date navigation
@@ -224,4 +506,12 @@ impl AppState {
     pub fn go_today(&mut self) {
         self.current_date = AppDate::today();
     }
+
+    pub fn prev_week(&mut self) {
+        self.current_date = self.current_date.add_days(-7);
+    }
+
+    pub fn next_week(&mut self) {
+        self.current_date = self.current_date.add_days(7);
+    }
 }
@@ -0,0 +1,47 @@
+use crate::{
+    api::{ApiError, Fetched},
+    utils::AppDate,
+};
+use osars::models::{Campus, College, Group, Schedule};
+
+/// Result of an API call that was run off the UI loop.
+///
+/// Every spawned task reports back through the app's channel with one of
+/// these; `App::tick` applies them and drops results that no longer match
+/// what the user is looking at. Schedule results carry the generation of
+/// the API client that fetched them, so results for a group, profile or
+/// server that is no longer selected are dropped even on the same date.
+pub enum TaskResult {
+    Schedules {
+        generation: u64,
+        date: AppDate,
+        background: bool,
+        result: Result<Fetched, ApiError>,
+    },
+    Week {
+        generation: u64,
+        start: AppDate,
+        result: Result<Vec<(AppDate, Fetched)>, ApiError>,
+    },
+    /// One day of the calendar month, fetched to count its lessons.
+    MonthDay {
+        generation: u64,
+        date: AppDate,
+        result: Result<Vec<Schedule>, ApiError>,
+    },
+    Colleges(Result<Vec<College>, ApiError>),
+    Campuses {
+        college_id: u32,
+        result: Result<Vec<Campus>, ApiError>,
+    },
+    Groups {
+        campus_id: u32,
+        result: Result<Vec<Group>, ApiError>,
+    },
+    /// Campuses of a college with the groups of all of them, for finding a
+    /// group without picking its campus first.
+    AllGroups {
+        college_id: u32,
+        result: Result<(Vec<Campus>, Vec<Group>), ApiError>,
+    },
+}
@@ -0,0 +1,160 @@
+use crate::utils::AppDate;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use osars::models::{Lesson, Schedule};
+use serde::Serialize;
+
+/// How schedules are printed by the command-line mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns for reading in a terminal
+    #[default]
+    Table,
+    Json,
+    Csv,
+    /// One line per lesson, for MOTD banners and status bars
+    Plain,
+}
+
+#[derive(Serialize)]
+struct Day<'a> {
+    date: NaiveDate,
+    lessons: Vec<&'a Lesson>,
+}
+
+/// Renders one or more days of schedules in `format`.
+pub fn render(days: &[(AppDate, Vec<Schedule>)], format: OutputFormat) -> anyhow::Result<String> {
+    let days: Vec<Day> = days
+        .iter()
+        .map(|(date, schedules)| {
+            let mut lessons: Vec<&Lesson> = schedules.iter().flat_map(|s| &s.lessons).collect();
+            lessons.sort_by_key(|l| (l.start_time, l.order));
+            Day {
+                date: (*date).into(),
+                lessons,
+            }
+        })
+        .collect();
+
+    Ok(match format {
+        OutputFormat::Table => render_table(&days),
+        OutputFormat::Json => serde_json::to_string_pretty(&days)? + "\n",
+        OutputFormat::Csv => render_csv(&days),
+        OutputFormat::Plain => render_plain(&days),
+    })
+}
+
+fn heading(date: NaiveDate) -> String {
+    date.format("%A %d.%m.%Y").to_string()
+}
+
+fn time_range(lesson: &Lesson) -> String {
+    format!(
+        "{}-{}",
+        lesson.start_time.format("%H:%M"),
+        lesson.end_time.format("%H:%M")
+    )
+}
+
+fn render_table(days: &[Day]) -> String {
+    let mut out = String::new();
+    for (i, day) in days.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(&heading(day.date));
+        out.push('\n');
+
+        if day.lessons.is_empty() {
+            out.push_str("No lessons\n");
+            continue;
+        }
+
+        let rows: Vec<[String; 5]> = day
+            .lessons
+            .iter()
+            .map(|l| {
+                [
+                    l.order.to_string(),
+                    time_range(l),
+                    l.title.clone(),
+                    l.cabinet.clone(),
+                    l.teacher.clone(),
+                ]
+            })
+            .collect();
+        let header = ["#", "Time", "Subject", "Room", "Teacher"].map(String::from);
+
+        let mut widths = [0; 5];
+        for row in std::iter::once(&header).chain(&rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in std::iter::once(&header).chain(&rows) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| {
+                    let pad = width - cell.chars().count();
+                    format!("{}{}", cell, " ".repeat(pad))
+                })
+                .collect();
+            out.push_str(line.join("  ").trim_end());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn render_plain(days: &[Day]) -> String {
+    let mut out = String::new();
+    for day in days {
+        if days.len() > 1 {
+            out.push_str(&heading(day.date));
+            out.push('\n');
+        }
+        if day.lessons.is_empty() {
+            out.push_str("No lessons\n");
+        }
+        for lesson in &day.lessons {
+            out.push_str(&format!(
+                "{} {} ({}, {})\n",
+                time_range(lesson),
+                lesson.title,
+                lesson.cabinet,
+                lesson.teacher
+            ));
+        }
+    }
+    out
+}
+
+fn render_csv(days: &[Day]) -> String {
+    let mut out = String::from("date,order,start,end,title,cabinet,teacher\n");
+    for day in days {
+        for lesson in &day.lessons {
+            let fields = [
+                day.date.to_string(),
+                lesson.order.to_string(),
+                lesson.start_time.format("%H:%M").to_string(),
+                lesson.end_time.format("%H:%M").to_string(),
+                csv_field(&lesson.title),
+                csv_field(&lesson.cabinet),
+                csv_field(&lesson.teacher),
+            ];
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,174 @@
+pub mod format;
+
+use crate::{
+    api::ApiClient,
+    config::{Config, Layer, Overrides, layers::CONFIG_ENV},
+    utils::{AppDate, timeline::format_age},
+};
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+pub use format::OutputFormat;
+use std::path::PathBuf;
+
+/// Terminal UI client for educational schedules.
+///
+/// Without a subcommand the interactive interface is started; the
+/// subcommands print the schedule to stdout and exit. The API and group
+/// flags apply to this run only and are never saved to the config file.
+#[derive(Debug, Parser)]
+#[command(name = "osatui", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Config file to use instead of the default one
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Profile from config.toml to use, `default` being the `[api]` section
+    #[arg(short, long, value_name = "NAME", global = true)]
+    pub profile: Option<String>,
+
+    /// Base URL of the schedule API
+    #[arg(long, value_name = "URL", global = true)]
+    pub api_url: Option<String>,
+
+    #[arg(long, value_name = "ID", global = true)]
+    pub college: Option<u32>,
+
+    #[arg(long, value_name = "ID", global = true)]
+    pub campus: Option<u32>,
+
+    #[arg(short, long, value_name = "ID", global = true)]
+    pub group: Option<u32>,
+
+    /// Theme from theme.toml
+    #[arg(long, value_name = "NAME", global = true)]
+    pub theme: Option<String>,
+
+    /// Neither read nor write the on-disk cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Date to show, such as 2026-10-20, 20.10, tomorrow, +3 or "next mon"
+    #[arg(short, long, global = true)]
+    pub date: Option<String>,
+
+    /// Output format of the printing subcommands
+    #[arg(short, long, value_enum, default_value_t, global = true)]
+    pub format: OutputFormat,
+}
+
+impl Cli {
+    pub fn overrides(&self) -> Overrides {
+        Overrides {
+            profile: self.profile.clone(),
+            api_url: self
+                .api_url
+                .as_ref()
+                .map(|url| url.trim_end_matches('/').to_string()),
+            college_id: self.college,
+            campus_id: self.campus,
+            group_id: self.group,
+            theme: self.theme.clone(),
+            cache_enabled: self.no_cache.then_some(false),
+            ..Overrides::default()
+        }
+    }
+
+    /// `--config`, else `$OSATUI_CONFIG`, else the default location.
+    pub fn config_path(&self) -> PathBuf {
+        self.config
+            .clone()
+            .or_else(|| std::env::var_os(CONFIG_ENV).map(PathBuf::from))
+            .unwrap_or_else(Config::config_path)
+    }
+
+    /// The `--date` flag resolved against today.
+    pub fn start_date(&self) -> anyhow::Result<Option<AppDate>> {
+        self.date
+            .as_deref()
+            .map(|input| AppDate::parse(input, AppDate::today()))
+            .transpose()
+            .with_context(|| format!("Invalid --date {:?}", self.date.as_deref().unwrap_or("")))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print today's schedule; use `show --date` for another day
+    Today,
+    /// Print the schedule of the --date day (today by default)
+    Show,
+    /// Print Monday–Saturday of the week containing --date
+    Week,
+    /// Print the effective configuration and where each value comes from
+    Config,
+}
+
+/// Runs a printing subcommand against the API described by `config`.
+pub async fn run(
+    command: Command,
+    date: Option<AppDate>,
+    format: OutputFormat,
+    config: Config,
+) -> anyhow::Result<()> {
+    if matches!(command, Command::Today) && date.is_some() {
+        anyhow::bail!("`today` always prints today's schedule, use `show --date` for another day");
+    }
+
+    let today = AppDate::today();
+    let date = date.unwrap_or(today);
+
+    let days = match command {
+        Command::Config => {
+            print!("{}", describe_config(&config));
+            return Ok(());
+        }
+        Command::Today => {
+            let api = ApiClient::new(config).await?;
+            vec![(today, api.fetch(&today).await?)]
+        }
+        Command::Show => {
+            let api = ApiClient::new(config).await?;
+            vec![(date, api.fetch(&date).await?)]
+        }
+        Command::Week => {
+            let api = ApiClient::new(config).await?;
+            api.fetch_week(date.week_start()).await?
+        }
+    };
+
+    if let Some(age) = days.iter().filter_map(|(_, f)| f.stale_age).max() {
+        eprintln!("offline – data from {}", format_age(age));
+    }
+
+    let days: Vec<_> = days
+        .into_iter()
+        .map(|(date, fetched)| (date, fetched.schedules))
+        .collect();
+    print!("{}", format::render(&days, format)?);
+    Ok(())
+}
+
+/// Lists the effective settings as TOML, annotating each with the layer
+/// that set it.
+pub fn describe_config(config: &Config) -> String {
+    let settings = config.effective();
+    let lines: Vec<String> = settings
+        .iter()
+        .map(|s| format!("{} = {}", s.key, s.value))
+        .collect();
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+    let mut out = format!("# {}\n", config.path().display());
+    for (line, setting) in lines.iter().zip(&settings) {
+        let source = match (setting.layer, setting.env) {
+            (Layer::Env, Some(var)) => format!("env {}", var),
+            (layer, _) => layer.to_string(),
+        };
+        let pad = width - line.chars().count();
+        out.push_str(&format!("{}{}  # {}\n", line, " ".repeat(pad), source));
+    }
+    out
+}
@@ -1,84 +1,759 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Key bindings, one section per mode. Entries that are left out are
+/// taken from the preset, so only the keys being changed need to be
+/// written.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "RawKeyMap")]
 pub struct KeyMap {
-    prev_day: KeyMapEntry,
-    cur_day: KeyMapEntry,
-    next_day: KeyMapEntry,
-    selector: KeyMapEntry,
-    settings: KeyMapEntry,
-    exit: KeyMapEntry,
+    #[serde(skip_serializing_if = "Preset::is_default")]
+    preset: Preset,
+    #[serde(skip_serializing_if = "NormalKeys::is_empty")]
+    normal: NormalKeys,
+    #[serde(skip_serializing_if = "SelectorKeys::is_empty")]
+    selector: SelectorKeys,
+    #[serde(skip_serializing_if = "WeekKeys::is_empty")]
+    week: WeekKeys,
+    #[serde(skip_serializing_if = "CalendarKeys::is_empty")]
+    calendar: CalendarKeys,
+    #[serde(skip_serializing_if = "SetupKeys::is_empty")]
+    setup: SetupKeys,
+}
+
+impl KeyMap {
+    /// Keys of the day view, also used to move through the week view.
+    pub fn normal(&self) -> NormalKeys {
+        self.normal
+            .clone()
+            .with_preset(&self.preset.keymap().normal)
+    }
+
+    /// Keys of the college/campus/group selector and the profile switcher.
+    pub fn selector(&self) -> SelectorKeys {
+        self.selector
+            .clone()
+            .with_preset(&self.preset.keymap().selector)
+    }
+
+    /// Keys of the week view besides the day view keys it moves by.
+    pub fn week(&self) -> WeekKeys {
+        self.week.clone().with_preset(&self.preset.keymap().week)
+    }
+
+    pub fn calendar(&self) -> CalendarKeys {
+        self.calendar
+            .clone()
+            .with_preset(&self.preset.keymap().calendar)
+    }
+
+    pub fn setup(&self) -> SetupKeys {
+        self.setup.clone().with_preset(&self.preset.keymap().setup)
+    }
+
+    pub fn preset(&self) -> Preset {
+        self.preset
+    }
+}
+
+/// A built-in set of bindings that the entries of `[keymap]` are applied
+/// on top of.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl Preset {
+    fn is_default(&self) -> bool {
+        *self == Preset::Default
+    }
+
+    fn keymap(self) -> &'static KeyMap {
+        static DEFAULT: LazyLock<KeyMap> = LazyLock::new(KeyMap::default);
+        static VIM: LazyLock<KeyMap> =
+            LazyLock::new(|| toml::from_str(VIM_PRESET).expect("vim preset is valid"));
+        static EMACS: LazyLock<KeyMap> =
+            LazyLock::new(|| toml::from_str(EMACS_PRESET).expect("emacs preset is valid"));
+
+        match self {
+            Preset::Default => &DEFAULT,
+            Preset::Vim => &VIM,
+            Preset::Emacs => &EMACS,
+        }
+    }
+}
+
+// Presets only list the entries that differ from the defaults.
+const VIM_PRESET: &str = r#"
+[normal]
+prev_day = ["Left", "h"]
+next_day = ["Right", "l"]
+goto_date = "/"
+
+[selector]
+up = ["Up", "k"]
+down = ["Down", "j"]
+prev_page = ["Left", "h", "C-b"]
+next_page = ["Right", "l", "C-f"]
+first = ["Home", "g g"]
+last = ["End", "G"]
+
+[calendar]
+left = ["Left", "h"]
+right = ["Right", "l"]
+up = ["Up", "k"]
+down = ["Down", "j"]
+prev_month = ["PageUp", "C-b"]
+next_month = ["PageDown", "C-f"]
+"#;
+
+const EMACS_PRESET: &str = r#"
+[normal]
+prev_day = ["Left", "C-b"]
+next_day = ["Right", "C-f"]
+goto_date = ["g", "M-g"]
+exit = ["q", "C-x C-c"]
+
+[selector]
+up = ["Up", "C-p"]
+down = ["Down", "C-n"]
+prev_page = ["Left", "M-v"]
+next_page = ["Right", "C-v"]
+first = ["Home", "M-<"]
+last = ["End", "M->"]
+filter = ["/", "C-s"]
+cancel = ["Esc", "C-g"]
+
+[week]
+back = ["Esc", "C-g"]
+
+[calendar]
+left = ["Left", "C-b"]
+right = ["Right", "C-f"]
+up = ["Up", "C-p"]
+down = ["Down", "C-n"]
+prev_month = ["PageUp", "M-v"]
+next_month = ["PageDown", "C-v"]
+back = ["Esc", "C-g"]
+
+[setup]
+next_field = ["Tab", "C-n"]
+prev_field = ["BackTab", "C-p"]
+cancel = ["Esc", "C-g"]
+"#;
+
+/// Defines a keymap section: a struct of optional entries with a getter
+/// per action that falls back to the preset's entry and then to the listed
+/// default keys, less any key an entry of another action took. Entries of
+/// the file take keys from the preset's entries the same way.
+macro_rules! key_section {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($action:ident: [$($default:literal),+],)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct $name {
+            $(
+                #[serde(skip_serializing_if = "Option::is_none")]
+                $action: Option<Keys>,
+            )+
+            /// The preset's entries, which give way to the ones above.
+            #[serde(skip)]
+            preset: Option<Box<$name>>,
+        }
+
+        impl $name {
+            $(
+                pub fn $action(&self) -> Bindings {
+                    if let Some(keys) = &self.$action {
+                        // Entries are checked when the keymap is loaded.
+                        return keys.parse().unwrap_or_default();
+                    }
+                    match self.preset.as_ref().and_then(|preset| preset.$action.as_ref()) {
+                        Some(keys) => keys.parse().unwrap_or_default().without(&self.entries()),
+                        None => Bindings::parse(&[$($default),+]).without(&self.bound()),
+                    }
+                }
+            )+
+
+            fn is_empty(&self) -> bool {
+                $(self.$action.is_none())&&+
+            }
+
+            /// Applies the entries on top of those of `preset`.
+            fn with_preset(self, preset: &Self) -> Self {
+                Self {
+                    preset: Some(Box::new(preset.clone())),
+                    ..self
+                }
+            }
+
+            /// Checks that every entry names known keys and that no two
+            /// entries share a key. Preset and default keys taken by an
+            /// entry are only logged, as configs written before the
+            /// defaults grew may reuse them, and a preset is picked for
+            /// most of its keys rather than all of them.
+            fn check(&self, section: &str) -> Result<(), String> {
+                $(
+                    if let Some(keys) = &self.$action {
+                        keys.parse().map_err(|e| {
+                            format!("keymap.{}.{}: {}", section, stringify!($action), e)
+                        })?;
+                    }
+                )+
+                let entries = self.entries();
+                check_conflicts(section, &entries)?;
+                let preset = self.preset.as_deref().map(Self::entries).unwrap_or_default();
+                $(
+                    if self.$action.is_none() {
+                        match preset.iter().find(|(action, _)| *action == stringify!($action)) {
+                            Some((action, keys)) => {
+                                log_shadowed(section, action, "preset", keys, &entries)
+                            }
+                            None => log_shadowed(
+                                section,
+                                stringify!($action),
+                                "default",
+                                &Bindings::parse(&[$($default),+]),
+                                &entries,
+                            ),
+                        }
+                    }
+                )+
+                Ok(())
+            }
+
+            /// The actions that have an entry, with its keys.
+            fn entries(&self) -> Vec<(&'static str, Bindings)> {
+                let mut entries = Vec::new();
+                $(
+                    if let Some(keys) = &self.$action {
+                        entries.push((stringify!($action), keys.parse().unwrap_or_default()));
+                    }
+                )+
+                entries
+            }
+
+            /// The actions that have an entry or a preset entry, with the
+            /// keys they are left with.
+            fn bound(&self) -> Vec<(&'static str, Bindings)> {
+                let mut bound = self.entries();
+                if let Some(preset) = &self.preset {
+                    for (action, keys) in preset.entries() {
+                        if !bound.iter().any(|(other, _)| *other == action) {
+                            bound.push((action, keys.without(&self.entries())));
+                        }
+                    }
+                }
+                bound
+            }
+
+            /// Every action of the section with its keys.
+            pub fn actions(&self) -> Vec<(&'static str, Bindings)> {
+                vec![$((stringify!($action), self.$action()),)+]
+            }
+        }
+    };
+}
+
+key_section! {
+    NormalKeys {
+        prev_day: ["Left"],
+        cur_day: ["Up"],
+        next_day: ["Right"],
+        week_view: ["w"],
+        calendar: ["c"],
+        goto_date: ["g"],
+        profiles: ["p"],
+        recent: ["r"],
+        selector: ["o"],
+        find_group: ["f"],
+        settings: ["s"],
+        reload: ["R"],
+        exit: ["q"],
+    }
+}
+
+key_section! {
+    SelectorKeys {
+        up: ["Up"],
+        down: ["Down"],
+        prev_page: ["Left"],
+        next_page: ["Right"],
+        first: ["Home"],
+        last: ["End"],
+        filter: ["/"],
+        find_group: ["Tab"],
+        favorite: ["*"],
+        back: ["Backspace"],
+        select: ["Enter"],
+        cancel: ["Esc"],
+    }
+}
+
+key_section! {
+    WeekKeys {
+        back: ["Esc"],
+    }
+}
+
+key_section! {
+    CalendarKeys {
+        left: ["Left"],
+        right: ["Right"],
+        up: ["Up"],
+        down: ["Down"],
+        prev_month: ["PageUp"],
+        next_month: ["PageDown"],
+        open: ["Enter"],
+        back: ["Esc"],
+    }
 }
 
+key_section! {
+    SetupKeys {
+        next_field: ["Tab"],
+        prev_field: ["BackTab"],
+        save: ["Enter"],
+        cancel: ["Esc"],
+    }
+}
+
+/// `[keymap]` as written in the file. Older versions kept the day view
+/// keys directly in `[keymap]`; they are read into `[keymap.normal]`, and
+/// `selector`/`calendar` can be either such a key or a section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawKeyMap {
+    preset: Preset,
+    normal: NormalKeys,
+    selector: Option<toml::Value>,
+    week: WeekKeys,
+    calendar: Option<toml::Value>,
+    setup: SetupKeys,
+    prev_day: Option<Keys>,
+    cur_day: Option<Keys>,
+    next_day: Option<Keys>,
+    week_view: Option<Keys>,
+    goto_date: Option<Keys>,
+    profiles: Option<Keys>,
+    settings: Option<Keys>,
+    exit: Option<Keys>,
+}
+
+impl TryFrom<RawKeyMap> for KeyMap {
+    type Error = toml::de::Error;
+
+    fn try_from(raw: RawKeyMap) -> Result<Self, Self::Error> {
+        let mut normal = raw.normal;
+        for (entry, legacy) in [
+            (&mut normal.prev_day, raw.prev_day),
+            (&mut normal.cur_day, raw.cur_day),
+            (&mut normal.next_day, raw.next_day),
+            (&mut normal.week_view, raw.week_view),
+            (&mut normal.goto_date, raw.goto_date),
+            (&mut normal.profiles, raw.profiles),
+            (&mut normal.settings, raw.settings),
+            (&mut normal.exit, raw.exit),
+        ] {
+            if entry.is_none() {
+                *entry = legacy;
+            }
+        }
+
+        let selector = match raw.selector {
+            Some(value) if is_entry(&value) => {
+                normal.selector = normal.selector.or(Some(value.try_into()?));
+                SelectorKeys::default()
+            }
+            Some(value) => value.try_into()?,
+            None => SelectorKeys::default(),
+        };
+        let calendar = match raw.calendar {
+            Some(value) if is_entry(&value) => {
+                normal.calendar = normal.calendar.or(Some(value.try_into()?));
+                CalendarKeys::default()
+            }
+            Some(value) => value.try_into()?,
+            None => CalendarKeys::default(),
+        };
+
+        let keymap = Self {
+            preset: raw.preset,
+            normal,
+            selector,
+            week: raw.week,
+            calendar,
+            setup: raw.setup,
+        };
+        keymap.check().map_err(serde::de::Error::custom)?;
+        Ok(keymap)
+    }
+}
+
+impl KeyMap {
+    fn check(&self) -> Result<(), String> {
+        self.normal().check("normal")?;
+        self.selector().check("selector")?;
+        self.week().check("week")?;
+        self.calendar().check("calendar")?;
+        self.setup().check("setup")
+    }
+}
+
+/// Reports two entries of a section bound to the same key, or one bound
+/// to the start of the other's sequence, which could then never be typed.
+fn check_conflicts(section: &str, actions: &[(&'static str, Bindings)]) -> Result<(), String> {
+    for (i, (action, keys)) in actions.iter().enumerate() {
+        for (other, other_keys) in &actions[i + 1..] {
+            if keys.iter().any(|sequence| other_keys.overlaps(sequence)) {
+                return Err(format!(
+                    "keymap.{section}.{action} and keymap.{section}.{other} are bound to the same key"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Logs the default or preset keys of `action`, as named by `origin`,
+/// that an entry of another action took.
+fn log_shadowed(
+    section: &str,
+    action: &str,
+    origin: &str,
+    fallback: &Bindings,
+    entries: &[(&'static str, Bindings)],
+) {
+    for (other, keys) in entries {
+        if fallback.iter().any(|sequence| keys.overlaps(sequence)) {
+            log::warn!(
+                "keymap.{section}.{other} takes a {origin} key of keymap.{section}.{action}, which keeps only its other keys"
+            );
+        }
+    }
+}
+
+/// Whether `value` is a key entry rather than a keymap section.
+fn is_entry(value: &toml::Value) -> bool {
+    match value {
+        toml::Value::Table(table) => table.contains_key("key"),
+        _ => true,
+    }
+}
+
+/// One key or a list of keys that all trigger the same action.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
-enum KeyMapEntry {
-    Simple(String),
-    WithModifier { key: String, ctrl: bool, alt: bool },
+enum Keys {
+    One(KeyMapEntry),
+    Many(Vec<KeyMapEntry>),
 }
 
-impl KeyMap {
-    pub fn prev_day(&self) -> KeyCode {
-        self.prev_day.parse()
+impl Keys {
+    fn parse(&self) -> Result<Bindings, String> {
+        let sequences = match self {
+            Keys::One(entry) => vec![entry.parse()?],
+            Keys::Many(entries) => entries
+                .iter()
+                .map(KeyMapEntry::parse)
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Bindings(sequences))
     }
+}
 
-    pub fn cur_day(&self) -> KeyCode {
-        self.cur_day.parse()
+/// The keys bound to an action. Each is a sequence of keys pressed one
+/// after another, such as `g g`, though most are a single key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bindings(Vec<Vec<KeyBinding>>);
+
+impl Bindings {
+    fn parse(names: &[&str]) -> Self {
+        Self(
+            names
+                .iter()
+                .map(|name| parse_sequence(name).expect("default keys are valid"))
+                .collect(),
+        )
     }
 
-    pub fn next_day(&self) -> KeyCode {
-        self.next_day.parse()
+    /// Whether the keys typed so far, `input`, trigger the action.
+    pub fn matches(&self, input: &[KeyEvent]) -> bool {
+        self.0
+            .iter()
+            .any(|sequence| sequence.len() == input.len() && starts_with(sequence, input))
     }
 
-    pub fn selector(&self) -> KeyCode {
-        self.selector.parse()
+    /// Whether `input` is the start of a longer sequence of the action, so
+    /// that further keys should be waited for.
+    pub fn continues(&self, input: &[KeyEvent]) -> bool {
+        self.0
+            .iter()
+            .any(|sequence| sequence.len() > input.len() && starts_with(sequence, input))
     }
 
-    pub fn settings(&self) -> KeyCode {
-        self.settings.parse()
+    /// Whether the action has no keys, e.g. after an entry took them.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    pub fn exit(&self) -> KeyCode {
-        self.exit.parse()
+    pub fn iter(&self) -> impl Iterator<Item = &[KeyBinding]> {
+        self.0.iter().map(Vec::as_slice)
     }
-}
 
-impl KeyMapEntry {
-    fn parse(&self) -> KeyCode {
-        let (key_str, _ctrl, _alt) = match self {
-            KeyMapEntry::Simple(s) => (s.as_str(), false, false),
-            KeyMapEntry::WithModifier { key, ctrl, alt } => (key.as_str(), *ctrl, *alt),
+    /// Whether `sequence` is one of the sequences or the start of one, or
+    /// one of them is the start of `sequence`.
+    fn overlaps(&self, sequence: &[KeyBinding]) -> bool {
+        let normalized = |keys: &[KeyBinding]| -> Vec<KeyBinding> {
+            keys.iter().map(KeyBinding::normalized).collect()
         };
+        let sequence = normalized(sequence);
+        self.iter()
+            .map(normalized)
+            .any(|other| other.starts_with(&sequence) || sequence.starts_with(&other))
+    }
+
+    /// The sequences that none of the `entries` overlap.
+    fn without(self, entries: &[(&'static str, Bindings)]) -> Self {
+        Self(
+            self.0
+                .into_iter()
+                .filter(|sequence| !entries.iter().any(|(_, keys)| keys.overlaps(sequence)))
+                .collect(),
+        )
+    }
 
-        match key_str {
-            "Left" | "left" => KeyCode::Left,
-            "Right" | "right" => KeyCode::Right,
-            "Up" | "up" => KeyCode::Up,
-            "Down" | "down" => KeyCode::Down,
-            "Enter" | "return" => KeyCode::Enter,
-            "Esc" | "escape" => KeyCode::Esc,
-            "Tab" => KeyCode::Tab,
-            "BackTab" => KeyCode::BackTab,
-            "Backspace" => KeyCode::Backspace,
-            "F1" => KeyCode::F(1),
-            "F2" => KeyCode::F(2),
-            "F3" => KeyCode::F(3),
-            s if s.len() == 1 => KeyCode::Char(s.chars().next().unwrap()),
-            _ => KeyCode::Null,
+    /// Adds the keys of `other`, e.g. to describe several actions at once.
+    pub fn extend(&mut self, other: &Bindings) {
+        self.0.extend(other.0.iter().cloned());
+    }
+}
+
+fn starts_with(sequence: &[KeyBinding], input: &[KeyEvent]) -> bool {
+    sequence
+        .iter()
+        .zip(input)
+        .all(|(key, event)| key.matches(event))
+}
+
+/// An entry is either a string such as `"q"`, `"C-o"`, `"Ctrl+Shift+r"` or
+/// `"g g"`, or a table with the key and its modifiers spelled out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum KeyMapEntry {
+    Simple(String),
+    WithModifier {
+        key: String,
+        #[serde(default)]
+        ctrl: bool,
+        #[serde(default)]
+        alt: bool,
+        #[serde(default)]
+        shift: bool,
+    },
+}
+
+/// A key together with the modifiers that have to be held for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Whether `event` triggers this binding.
+    ///
+    /// Terminals report Shift inconsistently, so a letter typed with Shift
+    /// is treated the same whether it arrives as `R`, as `r` with Shift,
+    /// or as `R` with Shift, and Shift is ignored for symbols like `?`
+    /// whose shifted form is already part of the character.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        normalize(self.code, self.modifiers) == normalize(event.code, event.modifiers)
+    }
+
+    /// The binding with Shift folded into letters as described in
+    /// [`KeyBinding::matches`].
+    pub fn normalized(&self) -> Self {
+        let (code, modifiers) = normalize(self.code, self.modifiers);
+        Self { code, modifiers }
+    }
+}
+
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    let mut modifiers =
+        modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+    let code = match code {
+        KeyCode::Char(c) if c.is_alphabetic() => {
+            if c.is_uppercase() {
+                modifiers |= KeyModifiers::SHIFT;
+            }
+            KeyCode::Char(c.to_lowercase().next().unwrap_or(c))
+        }
+        KeyCode::Char(_) | KeyCode::BackTab => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            code
+        }
+        other => other,
+    };
+    (code, modifiers)
+}
+
+impl KeyMapEntry {
+    fn parse(&self) -> Result<Vec<KeyBinding>, String> {
+        match self {
+            KeyMapEntry::Simple(s) => parse_sequence(s),
+            KeyMapEntry::WithModifier {
+                key,
+                ctrl,
+                alt,
+                shift,
+            } => {
+                let mut sequence = parse_sequence(key)?;
+                for binding in &mut sequence {
+                    for (held, modifier) in [
+                        (*ctrl, KeyModifiers::CONTROL),
+                        (*alt, KeyModifiers::ALT),
+                        (*shift, KeyModifiers::SHIFT),
+                    ] {
+                        if held {
+                            binding.modifiers |= modifier;
+                        }
+                    }
+                }
+                Ok(sequence)
+            }
         }
     }
 }
 
-impl Default for KeyMap {
-    fn default() -> Self {
-        Self {
-            prev_day: KeyMapEntry::Simple("Left".to_string()),
-            cur_day: KeyMapEntry::Simple("Up".to_string()),
-            next_day: KeyMapEntry::Simple("Right".to_string()),
-            selector: KeyMapEntry::Simple("o".to_string()),
-            settings: KeyMapEntry::Simple("s".to_string()),
-            exit: KeyMapEntry::Simple("q".to_string()),
+/// Parses a sequence of keys separated by spaces, like `g g` or
+/// `C-x C-c`.
+fn parse_sequence(s: &str) -> Result<Vec<KeyBinding>, String> {
+    let sequence = s
+        .split_whitespace()
+        .map(parse_binding)
+        .collect::<Result<Vec<_>, _>>()?;
+    if sequence.is_empty() {
+        return Err("no key given".to_string());
+    }
+    Ok(sequence)
+}
+
+/// Parses `C-o`, `Ctrl+o`, `M-x`, `Alt+x`, `S-Tab`, `Ctrl+Shift+r` and
+/// plain key names. Modifier prefixes are case-insensitive and may be
+/// joined with `-` or `+`.
+fn parse_binding(s: &str) -> Result<KeyBinding, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = s.trim();
+
+    while let Some(pos) = rest.find(['-', '+']) {
+        // A leading or trailing separator is the `-`/`+` key itself.
+        if pos == 0 || pos + 1 == rest.len() {
+            break;
         }
+        let modifier = match rest[..pos].to_lowercase().as_str() {
+            "c" | "ctrl" | "control" => KeyModifiers::CONTROL,
+            "m" | "a" | "alt" | "meta" => KeyModifiers::ALT,
+            "s" | "shift" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        modifiers |= modifier;
+        rest = &rest[pos + 1..];
+    }
+
+    let code = parse_key(rest).ok_or_else(|| format!("unknown key '{}'", s.trim()))?;
+    Ok(KeyBinding::new(code, modifiers))
+}
+
+/// Parses a single character or a key name. Names are case-insensitive,
+/// so `Left`, `left` and `LEFT` are the same key, while a single letter
+/// keeps its case: `G` is Shift+g.
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let lower = name.to_lowercase();
+    if let Some(n) = lower.strip_prefix('f')
+        && let Ok(n) = n.parse::<u8>()
+        && (1..=24).contains(&n)
+    {
+        return Some(KeyCode::F(n));
     }
+
+    let code = match lower.as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "enter" | "return" | "ret" | "cr" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" | "bs" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" | "pgdown" => KeyCode::PageDown,
+        "capslock" => KeyCode::CapsLock,
+        "scrolllock" => KeyCode::ScrollLock,
+        "numlock" => KeyCode::NumLock,
+        "printscreen" | "print" => KeyCode::PrintScreen,
+        "pause" => KeyCode::Pause,
+        "menu" => KeyCode::Menu,
+        "keypadbegin" => KeyCode::KeypadBegin,
+        "space" | "spc" => KeyCode::Char(' '),
+        "minus" | "dash" => KeyCode::Char('-'),
+        "plus" => KeyCode::Char('+'),
+        "equal" | "equals" => KeyCode::Char('='),
+        "underscore" => KeyCode::Char('_'),
+        "slash" => KeyCode::Char('/'),
+        "backslash" => KeyCode::Char('\\'),
+        "pipe" | "bar" => KeyCode::Char('|'),
+        "comma" => KeyCode::Char(','),
+        "period" | "dot" => KeyCode::Char('.'),
+        "colon" => KeyCode::Char(':'),
+        "semicolon" => KeyCode::Char(';'),
+        "quote" | "apostrophe" => KeyCode::Char('\''),
+        "doublequote" => KeyCode::Char('"'),
+        "backtick" | "grave" => KeyCode::Char('`'),
+        "tilde" => KeyCode::Char('~'),
+        "bang" | "exclamation" => KeyCode::Char('!'),
+        "question" => KeyCode::Char('?'),
+        "at" => KeyCode::Char('@'),
+        "hash" => KeyCode::Char('#'),
+        "dollar" => KeyCode::Char('$'),
+        "percent" => KeyCode::Char('%'),
+        "caret" => KeyCode::Char('^'),
+        "ampersand" => KeyCode::Char('&'),
+        "asterisk" | "star" => KeyCode::Char('*'),
+        "lparen" | "leftparen" => KeyCode::Char('('),
+        "rparen" | "rightparen" => KeyCode::Char(')'),
+        "lbracket" | "leftbracket" => KeyCode::Char('['),
+        "rbracket" | "rightbracket" => KeyCode::Char(']'),
+        "lbrace" | "leftbrace" => KeyCode::Char('{'),
+        "rbrace" | "rightbrace" => KeyCode::Char('}'),
+        "lt" | "less" => KeyCode::Char('<'),
+        "gt" | "greater" => KeyCode::Char('>'),
+        _ => return None,
+    };
+    Some(code)
 }
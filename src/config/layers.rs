@@ -0,0 +1,115 @@
+use crate::utils::Timezone;
+use anyhow::Context;
+use std::{fmt, path::PathBuf, str::FromStr};
+
+/// Settings for a single run that take precedence over the config file but
+/// are never written back to it. One set comes from `OSATUI_*` environment
+/// variables and one from command-line flags, which win over the former.
+#[derive(Clone, Debug, Default)]
+pub struct Overrides {
+    pub profile: Option<String>,
+    pub api_url: Option<String>,
+    pub college_id: Option<u32>,
+    pub campus_id: Option<u32>,
+    pub group_id: Option<u32>,
+    pub refresh_interval: Option<u64>,
+    pub cache_enabled: Option<bool>,
+    pub cache_dir: Option<PathBuf>,
+    pub cache_ttl: Option<u64>,
+    pub theme: Option<String>,
+    pub timezone: Option<String>,
+}
+
+/// Environment variable naming the config file to load.
+pub const CONFIG_ENV: &str = "OSATUI_CONFIG";
+
+impl Overrides {
+    /// Reads the `OSATUI_*` variables of the process environment.
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads overrides through `var`, which looks up a variable by name.
+    /// Empty variables are ignored; malformed ones are an error naming the
+    /// variable.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let get = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+
+        let overrides = Self {
+            profile: get("OSATUI_PROFILE"),
+            api_url: get("OSATUI_API_URL").map(|url| url.trim_end_matches('/').to_string()),
+            college_id: parse_var(&get, "OSATUI_COLLEGE_ID")?,
+            campus_id: parse_var(&get, "OSATUI_CAMPUS_ID")?,
+            group_id: parse_var(&get, "OSATUI_GROUP_ID")?,
+            refresh_interval: parse_var(&get, "OSATUI_REFRESH_INTERVAL")?,
+            cache_enabled: get("OSATUI_CACHE_ENABLED")
+                .map(|value| parse_bool(&value).context("Invalid OSATUI_CACHE_ENABLED"))
+                .transpose()?,
+            cache_dir: get("OSATUI_CACHE_DIR").map(PathBuf::from),
+            cache_ttl: parse_var(&get, "OSATUI_CACHE_TTL")?,
+            theme: get("OSATUI_THEME"),
+            timezone: get("OSATUI_TIMEZONE"),
+        };
+
+        if let Some(timezone) = &overrides.timezone {
+            timezone
+                .parse::<Timezone>()
+                .context("Invalid OSATUI_TIMEZONE")?;
+        }
+
+        Ok(overrides)
+    }
+}
+
+fn parse_var<T>(get: &impl Fn(&str) -> Option<String>, name: &str) -> anyhow::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    get(name)
+        .map(|value| value.trim().parse::<T>())
+        .transpose()
+        .with_context(|| format!("Invalid {}", name))
+}
+
+fn parse_bool(value: &str) -> anyhow::Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        other => anyhow::bail!("expected true or false, got '{}'", other),
+    }
+}
+
+/// Where the effective value of a setting came from, lowest to highest
+/// precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Layer::Default => "default",
+            Layer::File => "file",
+            Layer::Env => "env",
+            Layer::Cli => "cli",
+        })
+    }
+}
+
+/// One line of the effective configuration.
+#[derive(Clone, Debug)]
+pub struct Setting {
+    /// Dotted key as written in config.toml, e.g. `api.group_id` or
+    /// `profiles.sibling.group_id`
+    pub key: String,
+    /// Value formatted as TOML
+    pub value: String,
+    pub layer: Layer,
+    /// Environment variable that overrides the setting, if any
+    pub env: Option<&'static str>,
+}
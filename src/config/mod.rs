@@ -1,19 +1,37 @@
 pub mod keymap;
+pub mod layers;
 pub mod theme;
 
-use crate::config::{
-    keymap::KeyMap,
-    theme::{Theme, ThemeManager},
+use crate::{
+    config::{
+        keymap::KeyMap,
+        theme::{Theme, ThemeManager},
+    },
+    utils::Timezone,
 };
 use anyhow::Context;
+use chrono::NaiveDate;
+pub use layers::{Layer, Overrides, Setting};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
     #[serde(flatten)]
     inner: ConfigData,
     #[serde(skip)]
+    env: Overrides,
+    #[serde(skip)]
+    cli: Overrides,
+    // Dotted keys present in the config file, to tell them from defaults
+    #[serde(skip)]
+    file_keys: HashSet<String>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
     theme: Theme,
 }
 
@@ -21,7 +39,17 @@ pub struct Config {
 struct ConfigData {
     api: ApiConfig,
     app: AppConfig,
+    #[serde(default)]
     keymap: KeyMap,
+    /// Further groups to switch between; `[api]` is the `default` profile
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profiles: BTreeMap<String, ApiConfig>,
+    /// Groups switched to last, the latest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    recent: Vec<SavedGroup>,
+    /// Groups starred in the quick switcher
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    favorites: Vec<SavedGroup>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -30,48 +58,175 @@ struct ApiConfig {
     college_id: u32,
     campus_id: u32,
     group_id: u32,
+    /// Date shown when the profile was last switched away from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_date: Option<NaiveDate>,
+}
+
+/// Name of the profile stored in the `[api]` section.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// How many groups the recent list keeps.
+pub const MAX_RECENT: usize = 10;
+
+/// A group to switch back to from the quick switcher, with the names it
+/// was shown under when it was picked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedGroup {
+    pub url: String,
+    pub college_id: u32,
+    pub campus_id: u32,
+    pub group_id: u32,
+    pub group: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub campus: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub college: String,
+}
+
+impl SavedGroup {
+    /// Whether both entries point at the same group, whatever their names.
+    pub fn same_group(&self, other: &SavedGroup) -> bool {
+        self.url == other.url
+            && self.college_id == other.college_id
+            && self.campus_id == other.campus_id
+            && self.group_id == other.group_id
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct AppConfig {
     refresh_interval: u64,
     cache_enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cache_dir: Option<PathBuf>,
     cache_ttl: u64,
+    #[serde(default = "default_max_stale_age")]
+    max_stale_age: u64,
+    #[serde(default = "default_lists_cache_ttl")]
+    lists_cache_ttl: u64,
+    #[serde(default = "default_prefetch_before")]
+    prefetch_before: u32,
+    #[serde(default = "default_prefetch_after")]
+    prefetch_after: u32,
+    #[serde(default = "default_prefetch_concurrency")]
+    prefetch_concurrency: usize,
     current_theme: String,
+    #[serde(default = "default_timezone")]
+    timezone: String,
+}
+
+fn default_max_stale_age() -> u64 {
+    7 * 24 * 3600
+}
+
+fn default_lists_cache_ttl() -> u64 {
+    24 * 3600
+}
+
+fn default_prefetch_before() -> u32 {
+    2
+}
+
+fn default_prefetch_after() -> u32 {
+    7
+}
+
+fn default_prefetch_concurrency() -> usize {
+    4
+}
+
+fn default_timezone() -> String {
+    "local".to_string()
 }
 
 impl Config {
     pub async fn load() -> anyhow::Result<Self> {
-        let path = Self::config_path();
+        Self::load_from(
+            Self::config_path(),
+            Overrides::from_env()?,
+            Overrides::default(),
+        )
+        .await
+    }
 
-        let data: ConfigData = if path.exists() {
-            let content = tokio::fs::read_to_string(&path)
+    /// Loads the config file at `path`, creating it with defaults if it
+    /// does not exist. Settings resolve as defaults < file < `env` < `cli`.
+    pub async fn load_from(path: PathBuf, env: Overrides, cli: Overrides) -> anyhow::Result<Self> {
+        let content = if path.exists() {
+            tokio::fs::read_to_string(&path)
                 .await
-                .with_context(|| format!("Failed to read config from {:?}", path))?;
-            toml::from_str(&content).with_context(|| "Failed to parse config file")?
+                .with_context(|| format!("Failed to read config from {:?}", path))?
         } else {
             log::info!("Config not found, creating default at {:?}", path);
-            let default = ConfigData::default();
             let config = Self {
-                inner: default,
-                theme: Theme::default(),
+                path: path.clone(),
+                ..Self::default()
             };
             config.save().await?;
-            return Ok(config);
+            toml::to_string_pretty(&config.inner)?
         };
 
-        let theme: Theme = ThemeManager::load()
+        let data: ConfigData =
+            toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+        let table: toml::Table =
+            toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+
+        data.app
+            .timezone
+            .parse::<Timezone>()
+            .with_context(|| format!("Invalid app.timezone in {:?}", path))?;
+        if let Some(name) = &data.app.active_profile {
+            data.check_profile(name)
+                .with_context(|| format!("Invalid app.active_profile in {:?}", path))?;
+        }
+        for (layer, overrides) in [("environment", &env), ("command line", &cli)] {
+            if let Some(name) = &overrides.profile {
+                data.check_profile(name)
+                    .with_context(|| format!("Invalid profile from the {}", layer))?;
+            }
+        }
+
+        let mut config = Self {
+            inner: data,
+            env,
+            cli,
+            file_keys: dotted_keys(&table),
+            path,
+            theme: Theme::default(),
+        };
+        config.theme = config.load_theme().await;
+        Ok(config)
+    }
+
+    async fn load_theme(&self) -> Theme {
+        let path = self.path.with_file_name("theme.toml");
+        let name = self.current_theme();
+        match ThemeManager::load_from(&path)
             .await
             .unwrap_or_default()
-            .get(&data.app.current_theme)
-            .unwrap_or_default()
-            .clone();
-
-        Ok(Self { inner: data, theme })
+            .get(name)
+        {
+            Some(theme) => theme,
+            None => {
+                if self.layered(|o| o.theme.as_ref()).is_some() {
+                    log::warn!(
+                        "Theme '{}' not found in {:?}, using the default",
+                        name,
+                        path
+                    );
+                }
+                Theme::default()
+            }
+        }
     }
 
+    /// Writes the config file. Values that only come from overrides are
+    /// not saved; the file keeps its own.
     pub async fn save(&self) -> anyhow::Result<()> {
-        let path = Self::config_path();
+        let path = &self.path;
 
         if let Some(parent) = path.parent() {
             tokio::fs::create_dir_all(parent)
@@ -82,42 +237,107 @@ impl Config {
         let toml =
             toml::to_string_pretty(&self.inner).with_context(|| "Failed to serialize config")?;
 
-        tokio::fs::write(&path, toml)
+        tokio::fs::write(path, toml)
             .await
             .with_context(|| format!("Failed to write config to {:?}", path))?;
 
         Ok(())
     }
 
-    fn config_path() -> PathBuf {
+    pub fn config_path() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| ".".into())
             .join("osatui/config.toml")
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     // Getters
     pub fn api_url(&self) -> &str {
-        &self.inner.api.url
+        self.layered(|o| o.api_url.as_deref())
+            .unwrap_or(&self.api().url)
     }
 
     pub fn college_id(&self) -> u32 {
-        self.inner.api.college_id
+        self.layered(|o| o.college_id)
+            .unwrap_or(self.api().college_id)
     }
 
     pub fn campus_id(&self) -> u32 {
-        self.inner.api.campus_id
+        self.layered(|o| o.campus_id)
+            .unwrap_or(self.api().campus_id)
     }
 
     pub fn group_id(&self) -> u32 {
-        self.inner.api.group_id
+        self.layered(|o| o.group_id).unwrap_or(self.api().group_id)
+    }
+
+    pub fn refresh_interval(&self) -> u64 {
+        self.layered(|o| o.refresh_interval)
+            .unwrap_or(self.inner.app.refresh_interval)
     }
 
     pub fn cache_enabled(&self) -> bool {
-        self.inner.app.cache_enabled
+        self.layered(|o| o.cache_enabled)
+            .unwrap_or(self.inner.app.cache_enabled)
+    }
+
+    /// Root directory of the on-disk cache.
+    pub fn cache_dir(&self) -> PathBuf {
+        self.layered(|o| o.cache_dir.clone())
+            .or_else(|| self.inner.app.cache_dir.clone())
+            .unwrap_or_else(|| {
+                dirs::cache_dir()
+                    .unwrap_or_else(|| ".".into())
+                    .join("osatui")
+            })
     }
 
     pub fn cache_ttl(&self) -> u64 {
-        self.inner.app.cache_ttl
+        self.layered(|o| o.cache_ttl)
+            .unwrap_or(self.inner.app.cache_ttl)
+    }
+
+    /// How old an expired cache entry may be and still be shown when the
+    /// API is unreachable; `0` disables the offline fallback.
+    pub fn max_stale_age(&self) -> u64 {
+        self.inner.app.max_stale_age
+    }
+
+    /// How long the college, campus and group lists are reused before they
+    /// are requested again.
+    pub fn lists_cache_ttl(&self) -> u64 {
+        self.inner.app.lists_cache_ttl
+    }
+
+    /// Days before and after a loaded date that are fetched into the cache
+    /// in the background; both `0` disables prefetching.
+    pub fn prefetch_window(&self) -> (u32, u32) {
+        (
+            self.inner.app.prefetch_before,
+            self.inner.app.prefetch_after,
+        )
+    }
+
+    /// How many prefetch requests may run at the same time.
+    pub fn prefetch_concurrency(&self) -> usize {
+        self.inner.app.prefetch_concurrency.max(1)
+    }
+
+    pub fn timezone(&self) -> Timezone {
+        self.timezone_name().parse().unwrap_or_default()
+    }
+
+    fn timezone_name(&self) -> &str {
+        self.layered(|o| o.timezone.as_deref())
+            .unwrap_or(&self.inner.app.timezone)
+    }
+
+    pub fn current_theme(&self) -> &str {
+        self.layered(|o| o.theme.as_deref())
+            .unwrap_or(&self.inner.app.current_theme)
     }
 
     pub fn keymap(&self) -> &KeyMap {
@@ -128,21 +348,324 @@ impl Config {
         &self.theme
     }
 
-    // Setters
+    // Setters: a value chosen in the app replaces any override of it
     pub fn set_api_url(&mut self, url: String) {
-        self.inner.api.url = url.trim_end_matches('/').to_string();
+        self.clear_override(|o| o.api_url = None);
+        self.api_mut().url = url.trim_end_matches('/').to_string();
     }
 
     pub fn set_college(&mut self, id: u32) {
-        self.inner.api.college_id = id;
+        self.clear_override(|o| o.college_id = None);
+        self.api_mut().college_id = id;
     }
 
     pub fn set_campus(&mut self, id: u32) {
-        self.inner.api.campus_id = id;
+        self.clear_override(|o| o.campus_id = None);
+        self.api_mut().campus_id = id;
     }
 
     pub fn set_group(&mut self, id: u32) {
-        self.inner.api.group_id = id;
+        self.clear_override(|o| o.group_id = None);
+        self.api_mut().group_id = id;
+    }
+
+    /// Name of the profile whose API settings are in use.
+    pub fn active_profile(&self) -> &str {
+        self.layered(|o| o.profile.as_deref())
+            .or(self.inner.app.active_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// `default` followed by the `[profiles.*]` names in order.
+    pub fn profile_names(&self) -> Vec<&str> {
+        std::iter::once(DEFAULT_PROFILE)
+            .chain(self.inner.profiles.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// Makes `name` the active profile. Only the active profile's settings
+    /// change afterwards; the others are saved as they are. API overrides
+    /// of the run are dropped, as they were meant for the profile left.
+    pub fn set_active_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        self.inner.check_profile(name)?;
+        self.clear_override(|o| {
+            o.profile = None;
+            o.api_url = None;
+            o.college_id = None;
+            o.campus_id = None;
+            o.group_id = None;
+        });
+        self.inner.app.active_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+        Ok(())
+    }
+
+    /// Whether `group` is the group of the active profile.
+    pub fn is_current_group(&self, group: &SavedGroup) -> bool {
+        group.url == self.api_url()
+            && group.college_id == self.college_id()
+            && group.campus_id == self.campus_id()
+            && group.group_id == self.group_id()
+    }
+
+    /// Groups switched to last, the latest first.
+    pub fn recent(&self) -> &[SavedGroup] {
+        &self.inner.recent
+    }
+
+    pub fn favorites(&self) -> &[SavedGroup] {
+        &self.inner.favorites
+    }
+
+    /// Entries of the quick switcher: the favorites, then the recent groups
+    /// that are not among them.
+    pub fn saved_groups(&self) -> Vec<&SavedGroup> {
+        let recent = self
+            .inner
+            .recent
+            .iter()
+            .filter(|group| !self.is_favorite(group));
+        self.inner.favorites.iter().chain(recent).collect()
+    }
+
+    pub fn is_favorite(&self, group: &SavedGroup) -> bool {
+        self.inner.favorites.iter().any(|g| g.same_group(group))
+    }
+
+    /// Moves `group` to the front of the recent list, dropping the oldest
+    /// entry past [`MAX_RECENT`].
+    pub fn push_recent(&mut self, group: SavedGroup) {
+        let recent = &mut self.inner.recent;
+        recent.retain(|g| !g.same_group(&group));
+        recent.insert(0, group);
+        recent.truncate(MAX_RECENT);
+    }
+
+    /// Stars `group`, or unstars it when it is a favorite already. Returns
+    /// whether it is starred now.
+    pub fn toggle_favorite(&mut self, group: SavedGroup) -> bool {
+        let favorites = &mut self.inner.favorites;
+        let count = favorites.len();
+        favorites.retain(|g| !g.same_group(&group));
+        if favorites.len() < count {
+            return false;
+        }
+        favorites.push(group);
+        true
+    }
+
+    /// Date last viewed in the active profile, if recorded.
+    pub fn last_date(&self) -> Option<NaiveDate> {
+        self.api().last_date
+    }
+
+    pub fn set_last_date(&mut self, date: NaiveDate) {
+        self.api_mut().last_date = Some(date);
+    }
+
+    fn api(&self) -> &ApiConfig {
+        self.inner
+            .profiles
+            .get(self.active_profile())
+            .unwrap_or(&self.inner.api)
+    }
+
+    fn api_mut(&mut self) -> &mut ApiConfig {
+        let name = self.active_profile().to_string();
+        match self.inner.profiles.get_mut(&name) {
+            Some(profile) => profile,
+            None => &mut self.inner.api,
+        }
+    }
+
+    /// The highest-precedence override of a setting, if any.
+    fn layered<'a, T>(&'a self, get: impl Fn(&'a Overrides) -> Option<T>) -> Option<T> {
+        get(&self.cli).or_else(|| get(&self.env))
+    }
+
+    fn clear_override(&mut self, clear: impl Fn(&mut Overrides)) {
+        clear(&mut self.cli);
+        clear(&mut self.env);
+    }
+
+    fn layer_of(&self, key: &str, overridden: impl Fn(&Overrides) -> bool) -> Layer {
+        if overridden(&self.cli) {
+            Layer::Cli
+        } else if overridden(&self.env) {
+            Layer::Env
+        } else if self.file_keys.contains(key) {
+            Layer::File
+        } else {
+            Layer::Default
+        }
+    }
+
+    /// Every setting with its effective value and the layer it came from.
+    pub fn effective(&self) -> Vec<Setting> {
+        let app = &self.inner.app;
+        let string = |s: &str| toml::Value::String(s.to_string()).to_string();
+
+        // The API settings of a profile other than `default` live in its
+        // `[profiles.<name>]` section.
+        let api = match self.active_profile() {
+            DEFAULT_PROFILE => "api".to_string(),
+            name => format!("profiles.{}", name),
+        };
+        let api_key = |name: &str| format!("{}.{}", api, name);
+
+        let mut settings = Vec::new();
+        let mut push = |key: &str, env: Option<&'static str>, value: String, layer| {
+            settings.push(Setting {
+                key: key.to_string(),
+                value,
+                layer,
+                env,
+            })
+        };
+
+        push(
+            "app.active_profile",
+            Some("OSATUI_PROFILE"),
+            string(self.active_profile()),
+            self.layer_of("app.active_profile", |o| o.profile.is_some()),
+        );
+        push(
+            &api_key("url"),
+            Some("OSATUI_API_URL"),
+            string(self.api_url()),
+            self.layer_of(&api_key("url"), |o| o.api_url.is_some()),
+        );
+        push(
+            &api_key("college_id"),
+            Some("OSATUI_COLLEGE_ID"),
+            self.college_id().to_string(),
+            self.layer_of(&api_key("college_id"), |o| o.college_id.is_some()),
+        );
+        push(
+            &api_key("campus_id"),
+            Some("OSATUI_CAMPUS_ID"),
+            self.campus_id().to_string(),
+            self.layer_of(&api_key("campus_id"), |o| o.campus_id.is_some()),
+        );
+        push(
+            &api_key("group_id"),
+            Some("OSATUI_GROUP_ID"),
+            self.group_id().to_string(),
+            self.layer_of(&api_key("group_id"), |o| o.group_id.is_some()),
+        );
+        push(
+            "app.refresh_interval",
+            Some("OSATUI_REFRESH_INTERVAL"),
+            self.refresh_interval().to_string(),
+            self.layer_of("app.refresh_interval", |o| o.refresh_interval.is_some()),
+        );
+        push(
+            "app.cache_enabled",
+            Some("OSATUI_CACHE_ENABLED"),
+            self.cache_enabled().to_string(),
+            self.layer_of("app.cache_enabled", |o| o.cache_enabled.is_some()),
+        );
+        push(
+            "app.cache_dir",
+            Some("OSATUI_CACHE_DIR"),
+            string(&self.cache_dir().to_string_lossy()),
+            self.layer_of("app.cache_dir", |o| o.cache_dir.is_some()),
+        );
+        push(
+            "app.cache_ttl",
+            Some("OSATUI_CACHE_TTL"),
+            self.cache_ttl().to_string(),
+            self.layer_of("app.cache_ttl", |o| o.cache_ttl.is_some()),
+        );
+        push(
+            "app.max_stale_age",
+            None,
+            app.max_stale_age.to_string(),
+            self.layer_of("app.max_stale_age", |_| false),
+        );
+        push(
+            "app.lists_cache_ttl",
+            None,
+            app.lists_cache_ttl.to_string(),
+            self.layer_of("app.lists_cache_ttl", |_| false),
+        );
+        push(
+            "app.prefetch_before",
+            None,
+            app.prefetch_before.to_string(),
+            self.layer_of("app.prefetch_before", |_| false),
+        );
+        push(
+            "app.prefetch_after",
+            None,
+            app.prefetch_after.to_string(),
+            self.layer_of("app.prefetch_after", |_| false),
+        );
+        push(
+            "app.prefetch_concurrency",
+            None,
+            app.prefetch_concurrency.to_string(),
+            self.layer_of("app.prefetch_concurrency", |_| false),
+        );
+        push(
+            "app.current_theme",
+            Some("OSATUI_THEME"),
+            string(self.current_theme()),
+            self.layer_of("app.current_theme", |o| o.theme.is_some()),
+        );
+        push(
+            "app.timezone",
+            Some("OSATUI_TIMEZONE"),
+            string(self.timezone_name()),
+            self.layer_of("app.timezone", |o| o.timezone.is_some()),
+        );
+
+        settings
+    }
+}
+
+impl ConfigData {
+    fn check_profile(&self, name: &str) -> anyhow::Result<()> {
+        if name == DEFAULT_PROFILE || self.profiles.contains_key(name) {
+            return Ok(());
+        }
+        let known: Vec<&str> = std::iter::once(DEFAULT_PROFILE)
+            .chain(self.profiles.keys().map(String::as_str))
+            .collect();
+        anyhow::bail!(
+            "unknown profile '{}', expected one of: {}",
+            name,
+            known.join(", ")
+        )
+    }
+}
+
+/// Collects `section.key` for every value of `table`, descending into
+/// nested tables.
+fn dotted_keys(table: &toml::Table) -> HashSet<String> {
+    let mut keys = HashSet::new();
+    for (key, value) in table {
+        keys.insert(key.clone());
+        if let toml::Value::Table(inner) = value {
+            keys.extend(
+                dotted_keys(inner)
+                    .into_iter()
+                    .map(|sub| format!("{}.{}", key, sub)),
+            );
+        }
+    }
+    keys
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            inner: ConfigData::default(),
+            env: Overrides::default(),
+            cli: Overrides::default(),
+            file_keys: HashSet::new(),
+            path: Self::config_path(),
+            theme: Theme::default(),
+        }
     }
 }
 
@@ -154,14 +677,26 @@ impl Default for ConfigData {
                 college_id: 1,
                 campus_id: 1,
                 group_id: 1,
+                last_date: None,
             },
             app: AppConfig {
                 refresh_interval: 300,
                 cache_enabled: true,
+                active_profile: None,
+                cache_dir: None,
                 cache_ttl: 3600,
+                max_stale_age: default_max_stale_age(),
+                lists_cache_ttl: default_lists_cache_ttl(),
+                prefetch_before: default_prefetch_before(),
+                prefetch_after: default_prefetch_after(),
+                prefetch_concurrency: default_prefetch_concurrency(),
                 current_theme: "dark".to_string(),
+                timezone: default_timezone(),
             },
             keymap: KeyMap::default(),
+            profiles: BTreeMap::new(),
+            recent: Vec::new(),
+            favorites: Vec::new(),
         }
     }
 }
@@ -1,7 +1,7 @@
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Theme {
@@ -76,10 +76,12 @@ impl Default for ThemeManager {
 
 impl ThemeManager {
     pub async fn load() -> anyhow::Result<Self> {
-        let path = Self::theme_path();
+        Self::load_from(&Self::theme_path()).await
+    }
 
+    pub async fn load_from(path: &Path) -> anyhow::Result<Self> {
         let themes: HashMap<String, Theme> = if path.exists() {
-            let content = tokio::fs::read_to_string(&path).await?;
+            let content = tokio::fs::read_to_string(path).await?;
             toml::from_str(&content)?
         } else {
             HashMap::new()
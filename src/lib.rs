@@ -1,5 +1,6 @@
 pub mod api;
 pub mod app;
+pub mod cli;
 pub mod config;
 pub mod ui;
 pub mod utils;
@@ -1,23 +1,44 @@
+use clap::Parser;
 use crossterm::{
     ExecutableCommand,
     event::{self, Event, KeyEventKind},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use log::info;
-use osatui::{app::App, config::Config};
+use osatui::{
+    app::{App, AppMode},
+    cli::{self, Cli},
+    config::{Config, Overrides},
+    utils::clock::{self, Clock},
+};
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     pretty_env_logger::init();
+    let args = Cli::parse();
 
     info!("Starting osatui v{}", env!("CARGO_PKG_VERSION"));
 
-    let config = Config::load().await?;
+    let env = Overrides::from_env()?;
+    let config = Config::load_from(args.config_path(), env, args.overrides()).await?;
     info!("Configuration loaded successfully");
 
+    clock::set_clock(Clock::system(config.timezone()));
+    let start_date = args.start_date()?;
+
+    if let Some(command) = args.command {
+        return cli::run(command, start_date, args.format, config).await;
+    }
+
     let mut app = App::new(config).await?;
+    if let Some(date) = start_date
+        && app.state().mode == AppMode::Normal
+    {
+        app.state_mut().current_date = date;
+        app.load_schedules();
+    }
     info!("Application initialized");
 
     enable_raw_mode()?;
@@ -39,9 +60,10 @@ async fn run_app(
     app: &mut App,
 ) -> anyhow::Result<()> {
     let mut last_tick = tokio::time::Instant::now();
-    let tick_rate = tokio::time::Duration::from_millis(250);
+    let tick_rate = tokio::time::Duration::from_millis(100);
 
     loop {
+        app.tick();
         terminal.draw(|f| osatui::ui::render(f, app))?;
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
@@ -0,0 +1,13 @@
+use std::time::Instant;
+
+const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Returns the spinner glyph for an operation running since `started`.
+pub fn frame(started: Instant) -> char {
+    let step = started.elapsed().as_millis() / 100;
+    FRAMES[step as usize % FRAMES.len()]
+}
+
+pub fn loading_text(started: Instant) -> String {
+    format!("{} Loading…", frame(started))
+}
@@ -7,15 +7,165 @@ use ratatui::prelude::*;
 pub fn render(f: &mut Frame, app: &App) {
     match app.state().mode {
         AppMode::Normal => screens::normal::render(f, app),
+        AppMode::Week => screens::week::render(f, app),
+        AppMode::Calendar => screens::calendar::render(f, app),
         AppMode::Selector => screens::selector::render(f, app),
         AppMode::Setup => screens::setup::render(f, app),
     }
 
+    if let Some(ref input) = app.state().goto_input {
+        render_goto_popup(f, app, input);
+    }
+
+    if let Some(selected) = app.state().profile_picker {
+        render_profile_popup(f, app, selected);
+    }
+
+    if let Some(selected) = app.state().group_picker {
+        render_group_popup(f, app, selected);
+    }
+
     if let Some(ref error) = app.state().error_message {
         render_error_popup(f, error);
     }
 }
 
+fn render_goto_popup(f: &mut Frame, app: &App, input: &str) {
+    let theme = app.state().config.theme();
+    let area = f.area();
+    let popup_area = Rect {
+        x: area.width / 4,
+        y: area.height / 4,
+        width: area.width / 2,
+        height: 5,
+    }
+    .intersection(area);
+
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(2)])
+        .split(popup_area);
+
+    components::input::InputField::new("Go to date", input, true).render(
+        f,
+        chunks[0],
+        theme.highlight_color(),
+    );
+
+    let hint = ratatui::widgets::Paragraph::new("25.12 · 2026-12-25 · +3 · -1w · fri · next tue")
+        .style(Style::default().fg(theme.table_header_color()))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[1]);
+}
+
+fn render_profile_popup(f: &mut Frame, app: &App, selected: usize) {
+    let config = &app.state().config;
+    let theme = config.theme();
+    let names = config.profile_names();
+
+    let area = f.area();
+    let popup_area = Rect {
+        x: area.width / 3,
+        y: area.height / 4,
+        width: area.width / 3,
+        height: names.len() as u16 + 2,
+    }
+    .intersection(area);
+
+    let items: Vec<ratatui::widgets::ListItem> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let marker = if *name == config.active_profile() {
+                "● "
+            } else {
+                "  "
+            };
+            let style = if i == selected {
+                Style::default()
+                    .bg(theme.highlight_color())
+                    .fg(theme.background_color())
+            } else {
+                Style::default().fg(theme.text_color())
+            };
+            ratatui::widgets::ListItem::new(format!("{}{}", marker, name)).style(style)
+        })
+        .collect();
+
+    let list = ratatui::widgets::List::new(items).block(
+        ratatui::widgets::Block::default()
+            .title("Profile")
+            .borders(ratatui::widgets::Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight_color())),
+    );
+
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(list, popup_area);
+}
+
+fn render_group_popup(f: &mut Frame, app: &App, selected: usize) {
+    let config = &app.state().config;
+    let theme = config.theme();
+    let groups = config.saved_groups();
+
+    let area = f.area();
+    let popup_area = Rect {
+        x: area.width / 4,
+        y: area.height / 4,
+        width: area.width / 2,
+        height: groups.len() as u16 + 2,
+    }
+    .intersection(area);
+
+    let items: Vec<ratatui::widgets::ListItem> = groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| {
+            let marker = if config.is_current_group(group) {
+                "● "
+            } else {
+                "  "
+            };
+            let star = if config.is_favorite(group) {
+                "★ "
+            } else {
+                "  "
+            };
+            let place = [group.campus.as_str(), group.college.as_str()]
+                .into_iter()
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let label = if place.is_empty() {
+                group.group.clone()
+            } else {
+                format!("{} – {}", group.group, place)
+            };
+            let style = if i == selected {
+                Style::default()
+                    .bg(theme.highlight_color())
+                    .fg(theme.background_color())
+            } else {
+                Style::default().fg(theme.text_color())
+            };
+            ratatui::widgets::ListItem::new(format!("{}{}{}", marker, star, label)).style(style)
+        })
+        .collect();
+
+    let star_key = screens::normal::format_key(&config.keymap().selector().favorite());
+    let list = ratatui::widgets::List::new(items).block(
+        ratatui::widgets::Block::default()
+            .title(format!("Groups ({}: star)", star_key))
+            .borders(ratatui::widgets::Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight_color())),
+    );
+
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(list, popup_area);
+}
+
 fn render_error_popup(f: &mut Frame, error: &str) {
     let area = f.area();
     let popup_area = Rect {
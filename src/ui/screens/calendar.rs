@@ -0,0 +1,155 @@
+use crate::{app::App, ui::screens::normal::help_text, utils::AppDate};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+pub fn render(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    render_header(f, app, chunks[0]);
+    render_month(f, app, chunks[1]);
+    render_footer(f, app, chunks[2]);
+}
+
+fn render_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.state().config.theme();
+    let state = app.state();
+
+    let cursor = state.calendar_cursor;
+    let month_total: usize = cursor
+        .month_days()
+        .iter()
+        .filter_map(|date| state.lesson_counts.get(date))
+        .sum();
+
+    let header_text = if state.calendar_pending.is_empty() {
+        format!(" {} | {} lessons ", cursor.format_month(), month_total)
+    } else {
+        format!(
+            " {} | {} lessons | loading {} days… ",
+            cursor.format_month(),
+            month_total,
+            state.calendar_pending.len()
+        )
+    };
+
+    let header = Paragraph::new(header_text)
+        .style(
+            Style::default()
+                .bg(theme.header_bg_color())
+                .fg(theme.header_fg_color()),
+        )
+        .alignment(ratatui::layout::Alignment::Center);
+
+    f.render_widget(header, area);
+}
+
+fn render_month(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.state().config.theme();
+    let state = app.state();
+
+    let cursor = state.calendar_cursor;
+    let today = AppDate::today();
+    let first = cursor.month_start().week_start();
+
+    let rows: Vec<Row> = (0..6)
+        .map(|week| first.add_days(week * 7))
+        .take_while(|monday| *monday == first || monday.same_month(&cursor))
+        .map(|monday| {
+            let cells = (0..7).map(|offset| {
+                let date = monday.add_days(offset);
+                let annotation = if state.calendar_pending.contains(&date) {
+                    "…".to_string()
+                } else {
+                    match state.lesson_counts.get(&date) {
+                        Some(0) => "—".to_string(),
+                        Some(count) => format!("{} {}", count, "•".repeat((*count).min(6))),
+                        None => String::new(),
+                    }
+                };
+
+                let mut style = if date.same_month(&cursor) {
+                    Style::default().fg(theme.text_color())
+                } else {
+                    Style::default().fg(theme.border_color())
+                };
+                if date == today {
+                    style = style
+                        .fg(theme.highlight_color())
+                        .add_modifier(Modifier::BOLD);
+                }
+                if date == cursor {
+                    style = style
+                        .bg(theme.header_bg_color())
+                        .fg(theme.header_fg_color());
+                }
+
+                Cell::from(Text::from(vec![
+                    Line::from(format!(" {:>2}", date.day())),
+                    Line::from(format!(" {}", annotation)),
+                ]))
+                .style(style)
+            });
+            Row::new(cells).height(2)
+        })
+        .collect();
+
+    let table = Table::new(rows, [Constraint::Ratio(1, 7); 7])
+        .header(Row::new(WEEKDAYS).style(Style::default().fg(theme.table_header_color())))
+        .column_spacing(1)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_color()))
+                .title("Calendar"),
+        );
+
+    f.render_widget(table, area);
+}
+
+fn render_footer(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.state().config.theme();
+    let keymap = app.state().config.keymap();
+    let keys = keymap.calendar();
+
+    let mut moves = keys.left();
+    for more in [keys.right(), keys.up(), keys.down()] {
+        moves.extend(&more);
+    }
+    let mut months = keys.prev_month();
+    months.extend(&keys.next_month());
+    let mut back = keys.back();
+    back.extend(&keymap.normal().calendar());
+
+    let help_text = help_text(&[
+        (moves, "move"),
+        (months, "month"),
+        (keys.open(), "open day"),
+        (back, "back"),
+        (keymap.normal().exit(), "quit"),
+    ]);
+
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(theme.table_header_color()))
+        .alignment(ratatui::layout::Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(theme.border_color())),
+        );
+
+    f.render_widget(help, area);
+}
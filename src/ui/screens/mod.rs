@@ -1,3 +1,5 @@
+pub mod calendar;
 pub mod normal;
 pub mod selector;
 pub mod setup;
+pub mod week;
@@ -1,40 +1,128 @@
-use crate::app::App;
+use crate::{
+    app::{App, AppState},
+    config::keymap::{Bindings, KeyBinding},
+    ui::components::spinner,
+    utils::{
+        clock::clock,
+        timeline::{LessonStatus, format_age, format_countdown, lesson_status},
+    },
+};
+use crossterm::event::{KeyCode, KeyModifiers};
+use osars::models::Lesson;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
-    style::Style,
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table},
 };
 
 pub fn render(f: &mut Frame, app: &App) {
+    let status = current_status(app.state());
+    let ongoing = matches!(status, Some(LessonStatus::Ongoing { .. }));
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
+            Constraint::Length(if ongoing { 1 } else { 0 }),
             Constraint::Min(0),
             Constraint::Length(3),
         ])
         .split(f.area());
 
-    render_header(f, app, chunks[0]);
-    render_schedule(f, app, chunks[1]);
-    render_footer(f, app, chunks[2]);
+    render_header(f, app, status, chunks[0]);
+    if ongoing {
+        render_progress(f, app, status, chunks[1]);
+    }
+    render_schedule(f, app, status, chunks[2]);
+    render_footer(f, app, chunks[3]);
+}
+
+fn lessons(state: &AppState) -> Vec<&Lesson> {
+    state.schedules.iter().flat_map(|s| &s.lessons).collect()
+}
+
+/// Status of today's lessons, or `None` when another date is shown.
+fn current_status(state: &AppState) -> Option<LessonStatus> {
+    let now = clock().now();
+    if state.loading.is_some() || state.current_date != now.date().into() {
+        return None;
+    }
+    lesson_status(&lessons(state), now.time())
+}
+
+fn status_text(state: &AppState, status: LessonStatus) -> Option<String> {
+    let lessons = lessons(state);
+    match status {
+        LessonStatus::Ongoing {
+            remaining, next, ..
+        } => Some(match next {
+            Some(next) => format!(
+                "ends in {} | next: {}",
+                format_countdown(remaining),
+                lessons[next].title
+            ),
+            None => format!("last lesson ends in {}", format_countdown(remaining)),
+        }),
+        LessonStatus::Break { next, starts_in } => Some(format!(
+            "break ends in {} | next: {}",
+            format_countdown(starts_in),
+            lessons[next].title
+        )),
+        LessonStatus::Upcoming { next, starts_in } => Some(format!(
+            "next: {} in {}",
+            lessons[next].title,
+            format_countdown(starts_in)
+        )),
+        LessonStatus::Finished => None,
+    }
 }
 
-fn render_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+fn render_header(
+    f: &mut Frame,
+    app: &App,
+    status: Option<LessonStatus>,
+    area: ratatui::layout::Rect,
+) {
     let theme = app.state().config.theme();
     let state = app.state();
 
-    let date_str = state.current_date.format();
+    let mut date_str = state.current_date.format();
+    if state.config.profile_names().len() > 1 {
+        date_str = format!("[{}] {}", state.config.active_profile(), date_str);
+    }
 
     let lessons_count: usize = state.schedules.iter().map(|s| s.lessons.len()).sum();
-    let schedule_info = if lessons_count == 0 {
+    let mut schedule_info = if lessons_count == 0 {
         "No lessons".to_string()
     } else {
         format!("{} lessons", lessons_count)
     };
+    if let Some(text) = status.and_then(|status| status_text(state, status)) {
+        schedule_info = format!("{} | {}", schedule_info, text);
+    }
+    match (state.offline, state.stale_age) {
+        (true, Some(age)) => {
+            schedule_info = format!(
+                "{} | offline – data from {}",
+                schedule_info,
+                format_age(age)
+            )
+        }
+        (true, None) => schedule_info = format!("{} | offline", schedule_info),
+        _ => {}
+    }
 
-    let header_text = format!(" {} | {} ", date_str, schedule_info);
+    let header_text = match (state.loading, state.last_refresh) {
+        (Some(started), _) => format!(" {} | {} ", date_str, spinner::loading_text(started)),
+        (None, Some(at)) => format!(
+            " {} | {} | updated {} ",
+            date_str,
+            schedule_info,
+            at.format("%H:%M")
+        ),
+        (None, None) => format!(" {} | {} ", date_str, schedule_info),
+    };
 
     let header = Paragraph::new(header_text)
         .style(
@@ -47,10 +135,58 @@ fn render_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(header, area);
 }
 
-fn render_schedule(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+fn render_progress(
+    f: &mut Frame,
+    app: &App,
+    status: Option<LessonStatus>,
+    area: ratatui::layout::Rect,
+) {
+    let theme = app.state().config.theme();
+    let Some(LessonStatus::Ongoing {
+        index,
+        progress,
+        remaining,
+        ..
+    }) = status
+    else {
+        return;
+    };
+    let lessons = lessons(app.state());
+
+    let gauge = Gauge::default()
+        .gauge_style(
+            Style::default()
+                .fg(theme.highlight_color())
+                .bg(theme.background_color()),
+        )
+        .ratio(progress)
+        .label(format!(
+            "{} | {:.0}% | {} left",
+            lessons[index].title,
+            progress * 100.0,
+            format_countdown(remaining)
+        ));
+
+    f.render_widget(gauge, area);
+}
+
+fn render_schedule(
+    f: &mut Frame,
+    app: &App,
+    status: Option<LessonStatus>,
+    area: ratatui::layout::Rect,
+) {
     let theme = app.state().config.theme();
     let state = app.state();
 
+    if let Some(started) = state.loading {
+        let msg = Paragraph::new(spinner::loading_text(started))
+            .style(Style::default().fg(theme.highlight_color()))
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(msg, area);
+        return;
+    }
+
     if state.schedules.is_empty() || state.schedules.iter().all(|s| s.lessons.is_empty()) {
         let msg = Paragraph::new("No lessons for selected date")
             .style(Style::default().fg(theme.highlight_color()))
@@ -59,19 +195,32 @@ fn render_schedule(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         return;
     }
 
-    let rows: Vec<Row> = state
-        .schedules
-        .iter()
-        .flat_map(|s| {
-            s.lessons.iter().map(|l| {
-                Row::new(vec![
-                    Cell::from(l.start_time.format("%H:%M").to_string()),
-                    Cell::from(l.end_time.format("%H:%M").to_string()),
-                    Cell::from(l.title.clone()),
-                    Cell::from(l.cabinet.clone()),
-                    Cell::from(l.teacher.clone()),
-                ])
-            })
+    let current = status.and_then(|s| s.current());
+    let next = status.and_then(|s| s.next());
+
+    let rows: Vec<Row> = lessons(state)
+        .into_iter()
+        .enumerate()
+        .map(|(i, l)| {
+            let style = if Some(i) == current {
+                Style::default()
+                    .fg(theme.highlight_color())
+                    .add_modifier(Modifier::BOLD)
+            } else if Some(i) == next {
+                Style::default()
+                    .fg(theme.highlight_color())
+                    .add_modifier(Modifier::ITALIC)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(l.start_time.format("%H:%M").to_string()),
+                Cell::from(l.end_time.format("%H:%M").to_string()),
+                Cell::from(l.title.clone()),
+                Cell::from(l.cabinet.clone()),
+                Cell::from(l.teacher.clone()),
+            ])
+            .style(style)
         })
         .collect();
 
@@ -101,17 +250,23 @@ fn render_schedule(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 
 fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let theme = app.state().config.theme();
-    let keymap = app.state().config.keymap();
-
-    let help_text = format!(
-        "{}: prev day | {}: today | {}: next day | {}: select group | {}: settings | {}: quit",
-        format_key(keymap.prev_day()),
-        format_key(keymap.cur_day()),
-        format_key(keymap.next_day()),
-        format_key(keymap.selector()),
-        format_key(keymap.settings()),
-        format_key(keymap.exit())
-    );
+    let keys = app.state().config.keymap().normal();
+
+    let help_text = help_text(&[
+        (keys.prev_day(), "prev day"),
+        (keys.cur_day(), "today"),
+        (keys.next_day(), "next day"),
+        (keys.goto_date(), "go to"),
+        (keys.week_view(), "week"),
+        (keys.calendar(), "calendar"),
+        (keys.profiles(), "profile"),
+        (keys.recent(), "recent"),
+        (keys.selector(), "select group"),
+        (keys.find_group(), "find group"),
+        (keys.settings(), "settings"),
+        (keys.reload(), "reload"),
+        (keys.exit(), "quit"),
+    ]);
 
     let help = Paragraph::new(help_text)
         .style(Style::default().fg(theme.table_header_color()))
@@ -125,14 +280,63 @@ fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(help, area);
 }
 
-fn format_key(key: crossterm::event::KeyCode) -> String {
-    match key {
-        crossterm::event::KeyCode::Left => "←".to_string(),
-        crossterm::event::KeyCode::Right => "→".to_string(),
-        crossterm::event::KeyCode::Up => "↑".to_string(),
-        crossterm::event::KeyCode::Down => "↓".to_string(),
-        crossterm::event::KeyCode::Char(c) => c.to_string(),
-        crossterm::event::KeyCode::F(n) => format!("F{}", n),
-        _ => format!("{:?}", key),
+/// Formats the keys of an action for help texts, e.g. `←`, `q`, `Ctrl+O`,
+/// `↓/j` or `gg`.
+pub fn format_key(keys: &Bindings) -> String {
+    keys.iter()
+        .map(format_sequence)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Plain characters are written together like `gg`, other sequences are
+/// spaced like `Ctrl+X Ctrl+C`.
+fn format_sequence(sequence: &[KeyBinding]) -> String {
+    let plain = sequence.iter().all(|key| {
+        matches!(key.normalized().code, KeyCode::Char(_)) && key.normalized().modifiers.is_empty()
+    });
+    let keys: Vec<String> = sequence.iter().map(|key| format_binding(*key)).collect();
+    keys.join(if plain { "" } else { " " })
+}
+
+/// Joins `(keys, description)` pairs into a footer line like
+/// `←: prev day | q: quit`, leaving out actions without keys.
+pub fn help_text(entries: &[(Bindings, &str)]) -> String {
+    entries
+        .iter()
+        .filter(|(keys, _)| !keys.is_empty())
+        .map(|(keys, description)| format!("{}: {}", format_key(keys), description))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn format_binding(key: KeyBinding) -> String {
+    let KeyBinding { code, modifiers } = key.normalized();
+
+    let mut text = String::new();
+    for (modifier, name) in [
+        (KeyModifiers::CONTROL, "Ctrl+"),
+        (KeyModifiers::ALT, "Alt+"),
+        (KeyModifiers::SHIFT, "Shift+"),
+    ] {
+        if modifiers.contains(modifier) {
+            text.push_str(name);
+        }
+    }
+
+    match code {
+        KeyCode::Left => text.push('←'),
+        KeyCode::Right => text.push('→'),
+        KeyCode::Up => text.push('↑'),
+        KeyCode::Down => text.push('↓'),
+        KeyCode::BackTab => text.push_str("Shift+Tab"),
+        KeyCode::PageUp => text.push_str("PgUp"),
+        KeyCode::PageDown => text.push_str("PgDn"),
+        KeyCode::Char(' ') => text.push_str("Space"),
+        KeyCode::Char(c) if !modifiers.is_empty() => text.extend(c.to_uppercase()),
+        KeyCode::Char(c) => text.push(c),
+        KeyCode::F(n) => text.push_str(&format!("F{}", n)),
+        _ => text.push_str(&format!("{:?}", code)),
     }
+    text
 }
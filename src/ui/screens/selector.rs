@@ -1,4 +1,10 @@
-use crate::app::{App, AppState};
+use crate::{
+    app::{App, AppState},
+    ui::{
+        components::{input::InputField, spinner},
+        screens::normal::help_text,
+    },
+};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -11,6 +17,8 @@ pub enum SelectionStage {
     College,
     Campus,
     Group,
+    /// Groups of every campus of a college at once
+    FindGroup,
 }
 
 pub fn render(f: &mut Frame, app: &App) {
@@ -20,6 +28,7 @@ pub fn render(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
+            Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Min(8),
             Constraint::Length(3),
@@ -29,10 +38,15 @@ pub fn render(f: &mut Frame, app: &App) {
         .split(area);
 
     render_title(f, state, chunks[0]);
-    render_list(f, state, chunks[1]);
-    render_info(f, state, chunks[2]);
-    render_pagination(f, state, chunks[3]);
-    render_help(f, chunks[4]);
+    InputField::new("Filter", &state.filter, state.filter_focused).render(
+        f,
+        chunks[1],
+        state.config.theme().highlight_color(),
+    );
+    render_list(f, state, chunks[2]);
+    render_info(f, state, chunks[3]);
+    render_pagination(f, state, chunks[4]);
+    render_help(f, state, chunks[5]);
 }
 
 fn render_title(f: &mut Frame, state: &AppState, area: Rect) {
@@ -42,6 +56,7 @@ fn render_title(f: &mut Frame, state: &AppState, area: Rect) {
         SelectionStage::College => "Select College",
         SelectionStage::Campus => "Select Campus",
         SelectionStage::Group => "Select Group",
+        SelectionStage::FindGroup => "Find Group",
     };
 
     let header = Paragraph::new(title)
@@ -54,53 +69,67 @@ fn render_title(f: &mut Frame, state: &AppState, area: Rect) {
 fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
     let theme = state.config.theme();
 
-    let items: Vec<ListItem> = match state.selection_stage {
-        SelectionStage::College => state
-            .colleges
-            .iter()
-            .enumerate()
-            .map(|(i, c)| {
-                let style = if i == state.selected_index {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
-                ListItem::new(format!("{} (ID: {})", c.name, c.college_id)).style(style)
-            })
-            .collect(),
-        SelectionStage::Campus => state
-            .campuses
-            .iter()
-            .enumerate()
-            .map(|(i, c)| {
-                let style = if i == state.selected_index {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
-                ListItem::new(format!("{} (ID: {})", c.name, c.id)).style(style)
-            })
-            .collect(),
-        SelectionStage::Group => state
-            .groups
-            .iter()
-            .enumerate()
-            .map(|(i, g)| {
-                let style = if i == state.selected_index {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
-                ListItem::new(format!("{} (ID: {})", g.name, g.id)).style(style)
-            })
-            .collect(),
-    };
+    if let Some(started) = state.loading {
+        let msg = Paragraph::new(spinner::loading_text(started))
+            .style(Style::default().fg(theme.highlight_color()))
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(msg, area);
+        return;
+    }
+
+    let active = state.active_id();
+    let items: Vec<ListItem> = state
+        .page_items()
+        .iter()
+        .enumerate()
+        .filter_map(|(row, &i)| {
+            let name = match state.selection_stage {
+                SelectionStage::College => state
+                    .colleges
+                    .get(i)
+                    .map(|c| format!("{} (ID: {})", c.name, c.college_id)),
+                SelectionStage::Campus => state
+                    .campuses
+                    .get(i)
+                    .map(|c| format!("{} (ID: {})", c.name, c.id)),
+                SelectionStage::Group => state
+                    .groups
+                    .get(i)
+                    .map(|g| format!("{} (ID: {})", g.name, g.id)),
+                SelectionStage::FindGroup => state.groups.get(i).map(|g| {
+                    let campus = state
+                        .campuses
+                        .iter()
+                        .find(|c| c.id == g.campus_id)
+                        .map_or("?", |c| &c.name);
+                    format!("{} – {} (ID: {})", g.name, campus, g.id)
+                }),
+            }?;
+
+            // The entry in use is marked like the active profile.
+            let is_active = state.item_id(i) == Some(active);
+            let marker = if is_active { "● " } else { "  " };
+            let style = if row == state.selected_index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if is_active {
+                Style::default().fg(theme.highlight_color())
+            } else {
+                Style::default()
+            };
+            let star = match state.selection_stage {
+                SelectionStage::Group | SelectionStage::FindGroup => state
+                    .groups
+                    .get(i)
+                    .filter(|g| state.config.is_favorite(&state.saved_group(g)))
+                    .map_or("", |_| " ★"),
+                _ => "",
+            };
+            Some(ListItem::new(format!("{}{}{}", marker, name, star)).style(style))
+        })
+        .collect();
 
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL))
@@ -112,32 +141,49 @@ fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
     f.render_stateful_widget(list, area, &mut list_state);
 }
 
+/// Renders the choices made so far as a breadcrumb, e.g.
+/// `Colleges › College › Campus`.
 fn render_info(f: &mut Frame, state: &AppState, area: Rect) {
     let theme = state.config.theme();
 
-    let info_text = match state.selection_stage {
-        SelectionStage::College => "Select a college from the list".to_string(),
-        SelectionStage::Campus => format!(
-            "College: {}",
-            state
-                .selected_college
-                .as_ref()
-                .map_or("Not selected", |c| &c.name)
-        ),
-        SelectionStage::Group => format!(
-            "College: {}, Campus: {}",
-            state
-                .selected_college
-                .as_ref()
-                .map_or("Not selected", |c| &c.name),
-            state
-                .selected_campus
-                .as_ref()
-                .map_or("Not selected", |c| &c.name)
-        ),
+    let college = |id: Option<u32>| {
+        state
+            .selected_college
+            .iter()
+            .chain(&state.colleges)
+            .find(|c| Some(c.college_id) == id)
+            .map_or_else(
+                || id.map_or("Not selected".to_string(), |id| format!("College {}", id)),
+                |c| c.name.clone(),
+            )
     };
 
-    let info = Paragraph::new(info_text)
+    let mut crumbs = vec!["Colleges".to_string()];
+    match state.selection_stage {
+        SelectionStage::College => {}
+        SelectionStage::Campus => {
+            crumbs.push(college(
+                state.selected_college.as_ref().map(|c| c.college_id),
+            ));
+        }
+        SelectionStage::Group => {
+            crumbs.push(college(
+                state.selected_college.as_ref().map(|c| c.college_id),
+            ));
+            crumbs.push(
+                state
+                    .selected_campus
+                    .as_ref()
+                    .map_or("Not selected".to_string(), |c| c.name.clone()),
+            );
+        }
+        SelectionStage::FindGroup => {
+            crumbs.push(college(state.find_college_id));
+            crumbs.push("All campuses".to_string());
+        }
+    }
+
+    let info = Paragraph::new(crumbs.join(" › "))
         .style(Style::default().fg(theme.table_header_color()))
         .alignment(ratatui::layout::Alignment::Center);
 
@@ -150,20 +196,21 @@ fn render_pagination(f: &mut Frame, state: &AppState, area: Rect) {
     let total_items = match state.selection_stage {
         SelectionStage::College => state.colleges.len(),
         SelectionStage::Campus => state.campuses.len(),
-        SelectionStage::Group => state.groups.len(),
+        SelectionStage::Group | SelectionStage::FindGroup => state.groups.len(),
+    };
+    let matching = state.filtered.len();
+    let items = if matching == total_items {
+        format!("{} items", total_items)
+    } else {
+        format!("{} of {} items", matching, total_items)
     };
 
-    let total_pages = (total_items + state.page_size - 1) / state.page_size.max(1);
+    let total_pages = matching.div_ceil(state.page_size.max(1));
 
     let pagination_text = if total_pages > 1 {
-        format!(
-            "Page {}/{} ({} items) ←→",
-            state.page + 1,
-            total_pages,
-            total_items
-        )
+        format!("Page {}/{} ({})", state.page + 1, total_pages, items)
     } else {
-        format!("Total items: {}", total_items)
+        format!("Total: {}", items)
     };
 
     let pagination = Paragraph::new(pagination_text)
@@ -173,8 +220,26 @@ fn render_pagination(f: &mut Frame, state: &AppState, area: Rect) {
     f.render_widget(pagination, area);
 }
 
-fn render_help(f: &mut Frame, area: Rect) {
-    let help_text = "↑↓: navigate | ←→: pages | Enter: select | Esc: cancel";
+fn render_help(f: &mut Frame, state: &AppState, area: Rect) {
+    let keys = state.config.keymap().selector();
+
+    let mut navigate = keys.up();
+    navigate.extend(&keys.down());
+    let mut pages = keys.prev_page();
+    pages.extend(&keys.next_page());
+
+    let help_text = help_text(&[
+        (navigate, "navigate"),
+        (pages, "pages"),
+        (keys.first(), "first"),
+        (keys.last(), "last"),
+        (keys.filter(), "filter"),
+        (keys.find_group(), "all campuses"),
+        (keys.favorite(), "star"),
+        (keys.back(), "back"),
+        (keys.select(), "select"),
+        (keys.cancel(), "cancel"),
+    ]);
     let help = Paragraph::new(help_text)
         .alignment(ratatui::layout::Alignment::Center)
         .block(Block::default().borders(Borders::TOP));
@@ -1,4 +1,7 @@
-use crate::app::{App, state::SetupField};
+use crate::{
+    app::{App, state::SetupField},
+    ui::screens::normal::help_text,
+};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
@@ -67,7 +70,15 @@ pub fn render(f: &mut Frame, app: &App) {
         f.render_widget(input, field_chunks[i]);
     }
 
-    let help = Paragraph::new("Enter: save | Tab: next field | Shift+Tab: prev field | Esc: exit")
+    let keys = state.config.keymap().setup();
+    let help_text = help_text(&[
+        (keys.save(), "save"),
+        (keys.next_field(), "next field"),
+        (keys.prev_field(), "prev field"),
+        (keys.cancel(), "exit"),
+    ]);
+
+    let help = Paragraph::new(help_text)
         .style(Style::default().fg(theme.table_header_color()))
         .alignment(ratatui::layout::Alignment::Center);
 
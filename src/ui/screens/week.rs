@@ -0,0 +1,182 @@
+use crate::{
+    app::App,
+    ui::{components::spinner, screens::normal::help_text},
+    utils::AppDate,
+};
+use osars::models::Schedule;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+pub fn render(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    render_header(f, app, chunks[0]);
+    render_grid(f, app, chunks[1]);
+    render_footer(f, app, chunks[2]);
+}
+
+fn render_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.state().config.theme();
+    let state = app.state();
+
+    let start = state.current_date.week_start();
+    let range = format!(
+        "{} – {}",
+        start.format_short(),
+        start.add_days(5).format_short()
+    );
+
+    let lessons_count: usize = state
+        .week
+        .iter()
+        .flat_map(|(_, schedules)| schedules)
+        .map(|s| s.lessons.len())
+        .sum();
+
+    let header_text = match state.loading {
+        Some(started) => format!(" Week {} | {} ", range, spinner::loading_text(started)),
+        None => format!(" Week {} | {} lessons ", range, lessons_count),
+    };
+
+    let header = Paragraph::new(header_text)
+        .style(
+            Style::default()
+                .bg(theme.header_bg_color())
+                .fg(theme.header_fg_color()),
+        )
+        .alignment(ratatui::layout::Alignment::Center);
+
+    f.render_widget(header, area);
+}
+
+fn render_grid(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.state().config.theme();
+    let state = app.state();
+
+    if let Some(started) = state.loading {
+        let msg = Paragraph::new(spinner::loading_text(started))
+            .style(Style::default().fg(theme.highlight_color()))
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(msg, area);
+        return;
+    }
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let cells: Vec<Rect> = rows
+        .iter()
+        .flat_map(|row| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                ])
+                .split(*row)
+                .to_vec()
+        })
+        .collect();
+
+    let today = AppDate::today();
+    for ((date, schedules), cell) in state.week.iter().zip(cells) {
+        render_day(f, app, *date, schedules, *date == today, cell);
+    }
+}
+
+fn render_day(
+    f: &mut Frame,
+    app: &App,
+    date: AppDate,
+    schedules: &[Schedule],
+    is_today: bool,
+    area: Rect,
+) {
+    let theme = app.state().config.theme();
+
+    let lines: Vec<Line> = schedules
+        .iter()
+        .flat_map(|s| &s.lessons)
+        .map(|l| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", l.start_time.format("%H:%M")),
+                    Style::default().fg(theme.table_header_color()),
+                ),
+                Span::raw(l.title.clone()),
+                Span::styled(
+                    format!(" {}", l.cabinet),
+                    Style::default().fg(theme.border_color()),
+                ),
+            ])
+        })
+        .collect();
+
+    let lines = if lines.is_empty() {
+        vec![Line::from("No lessons")]
+    } else {
+        lines
+    };
+
+    let border_style = if is_today {
+        Style::default()
+            .fg(theme.highlight_color())
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.border_color())
+    };
+
+    let day = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text_color()))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title(date.format_short()),
+        );
+
+    f.render_widget(day, area);
+}
+
+fn render_footer(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.state().config.theme();
+    let keymap = app.state().config.keymap();
+    let keys = keymap.normal();
+    let mut day_view = keys.week_view();
+    day_view.extend(&keymap.week().back());
+
+    let help_text = help_text(&[
+        (keys.prev_day(), "prev week"),
+        (keys.cur_day(), "this week"),
+        (keys.next_day(), "next week"),
+        (day_view, "day view"),
+        (keys.exit(), "quit"),
+    ]);
+
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(theme.table_header_color()))
+        .alignment(ratatui::layout::Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(theme.border_color())),
+        );
+
+    f.render_widget(help, area);
+}
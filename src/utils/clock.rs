@@ -0,0 +1,134 @@
+use crate::utils::AppDate;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use std::{str::FromStr, sync::RwLock};
+
+/// Timezone used to decide what "now" and "today" are.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Timezone {
+    /// Whatever the operating system reports.
+    #[default]
+    Local,
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl Timezone {
+    /// Wall-clock time in this timezone at the instant `at`.
+    pub fn wall_time(&self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Timezone::Local => at.with_timezone(&Local).naive_local(),
+            Timezone::Fixed(offset) => at.with_timezone(offset).naive_local(),
+            Timezone::Named(tz) => at.with_timezone(tz).naive_local(),
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = anyhow::Error;
+
+    /// Accepts `local`, `UTC`, offsets such as `+05:00`, `+5` or `UTC+5`,
+    /// and IANA names such as `Asia/Yekaterinburg`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        match s.to_lowercase().as_str() {
+            "" | "local" | "system" => return Ok(Timezone::Local),
+            "utc" | "z" => return Ok(Timezone::Fixed(FixedOffset::east_opt(0).unwrap())),
+            _ => {}
+        }
+
+        let upper = s.to_uppercase();
+        let offset = upper
+            .strip_prefix("UTC")
+            .or_else(|| upper.strip_prefix("GMT"))
+            .unwrap_or(&upper);
+        if offset.starts_with(['+', '-']) {
+            return parse_offset(offset).map(Timezone::Fixed);
+        }
+
+        s.parse::<Tz>().map(Timezone::Named).map_err(|_| {
+            anyhow::anyhow!(
+                "unknown timezone '{}', use an IANA name like Asia/Yekaterinburg or an offset like +05:00",
+                s
+            )
+        })
+    }
+}
+
+fn parse_offset(s: &str) -> anyhow::Result<FixedOffset> {
+    let invalid = || anyhow::anyhow!("invalid UTC offset '{}', expected e.g. +05:00", s);
+
+    let (sign, rest) = s.split_at(1);
+    let sign = if sign == "-" { -1 } else { 1 };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+/// Source of the current time. Every "now" in the app goes through the
+/// process-wide clock so the timezone is applied consistently and tests
+/// can pin the time.
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    timezone: Timezone,
+    fixed: Option<DateTime<Utc>>,
+}
+
+impl Clock {
+    pub const fn system(timezone: Timezone) -> Self {
+        Self {
+            timezone,
+            fixed: None,
+        }
+    }
+
+    /// A clock frozen at `at`.
+    pub const fn fixed(at: DateTime<Utc>, timezone: Timezone) -> Self {
+        Self {
+            timezone,
+            fixed: Some(at),
+        }
+    }
+
+    pub fn timezone(&self) -> Timezone {
+        self.timezone
+    }
+
+    pub fn now_utc(&self) -> DateTime<Utc> {
+        self.fixed.unwrap_or_else(Utc::now)
+    }
+
+    /// Wall-clock time in the configured timezone.
+    pub fn now(&self) -> NaiveDateTime {
+        self.timezone.wall_time(self.now_utc())
+    }
+
+    pub fn today(&self) -> AppDate {
+        self.now().date().into()
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::system(Timezone::Local)
+    }
+}
+
+static CLOCK: RwLock<Clock> = RwLock::new(Clock::system(Timezone::Local));
+
+/// Replaces the process-wide clock.
+pub fn set_clock(clock: Clock) {
+    *CLOCK.write().unwrap_or_else(|e| e.into_inner()) = clock;
+}
+
+pub fn clock() -> Clock {
+    *CLOCK.read().unwrap_or_else(|e| e.into_inner())
+}
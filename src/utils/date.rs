@@ -1,11 +1,18 @@
-use chrono::{Duration, NaiveDate, Utc};
+use crate::utils::clock::clock;
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Years a parsed date may fall in. Far enough from the limits of
+/// `NaiveDate` that stepping through days and weeks never overflows.
+const SUPPORTED_YEARS: RangeInclusive<i32> = 1900..=9999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct AppDate(NaiveDate);
 
 impl AppDate {
+    /// Today according to the process-wide clock and its timezone.
     pub fn today() -> Self {
-        Self(Utc::now().date_naive())
+        clock().today()
     }
 
     pub fn prev(self) -> Self {
@@ -16,8 +23,75 @@ impl AppDate {
         Self(self.0 + Duration::days(1))
     }
 
+    pub fn add_days(self, days: i64) -> Self {
+        Self(self.0 + Duration::days(days))
+    }
+
+    /// Moves by `days`, or returns `None` past the range of dates.
+    pub fn checked_add_days(self, days: i64) -> Option<Self> {
+        Duration::try_days(days)
+            .and_then(|delta| self.0.checked_add_signed(delta))
+            .map(Self)
+    }
+
+    /// Monday of the week this date belongs to.
+    pub fn week_start(self) -> Self {
+        let offset = self.0.weekday().num_days_from_monday() as i64;
+        self.add_days(-offset)
+    }
+
+    pub fn month_start(self) -> Self {
+        self.add_days(1 - self.0.day() as i64)
+    }
+
+    /// Moves by whole months, clamping the day to the target month's length.
+    pub fn add_months(self, months: i32) -> Self {
+        let shifted = if months >= 0 {
+            self.0.checked_add_months(Months::new(months as u32))
+        } else {
+            self.0
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        };
+        Self(shifted.unwrap_or(self.0))
+    }
+
+    /// Every date of the month this date belongs to.
+    pub fn month_days(self) -> Vec<Self> {
+        let start = self.month_start();
+        (0..31)
+            .map(|offset| start.add_days(offset))
+            .take_while(|date| date.0.month() == start.0.month())
+            .collect()
+    }
+
+    pub fn same_month(&self, other: &Self) -> bool {
+        self.0.year() == other.0.year() && self.0.month() == other.0.month()
+    }
+
+    pub fn day(&self) -> u32 {
+        self.0.day()
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        self.0.weekday()
+    }
+
+    pub fn format_month(&self) -> String {
+        self.0.format("%B %Y").to_string()
+    }
+
+    pub fn format_short(&self) -> String {
+        self.0.format("%a %d.%m").to_string()
+    }
+
     pub fn format(&self) -> String {
-        let today = Utc::now().date_naive();
+        self.format_relative_to(clock().today())
+    }
+
+    /// Formats the date as `Today`, `Tomorrow` or `Yesterday` relative to
+    /// `today`, or in full otherwise.
+    pub fn format_relative_to(&self, today: AppDate) -> String {
+        let today = today.0;
         if self.0 == today {
             "Today".into()
         } else if self.0 == today + Duration::days(1) {
@@ -32,6 +106,143 @@ impl AppDate {
     pub fn iso(&self) -> String {
         self.0.format("%d-%m-%Y").to_string()
     }
+
+    /// Parses user input relative to `today`.
+    ///
+    /// Accepts absolute dates (`25.12`, `25.12.2026`, `2026-12-25`),
+    /// offsets (`+3`, `-1w`, `+2m`), keywords (`today`, `tomorrow`,
+    /// `yesterday`) and weekday names (`mon`, `friday`, `next tue`,
+    /// `last fri`). Dates outside the years 1900 to 9999 are rejected.
+    pub fn parse(input: &str, today: AppDate) -> anyhow::Result<Self> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() {
+            anyhow::bail!("enter a date, an offset like +3 or a weekday");
+        }
+
+        let date = parse_unchecked(&input, today)?;
+        if !SUPPORTED_YEARS.contains(&date.0.year()) {
+            return Err(unsupported_year(&input));
+        }
+        Ok(date)
+    }
+}
+
+fn unsupported_year(input: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "'{}' is outside the supported years {} to {}",
+        input,
+        SUPPORTED_YEARS.start(),
+        SUPPORTED_YEARS.end()
+    )
+}
+
+fn parse_unchecked(input: &str, today: AppDate) -> anyhow::Result<AppDate> {
+    match input {
+        "today" | "now" => return Ok(today),
+        "tomorrow" => return Ok(today.next()),
+        "yesterday" => return Ok(today.prev()),
+        _ => {}
+    }
+
+    if input.starts_with(['+', '-']) {
+        return parse_offset(input, today);
+    }
+
+    if let Some((modifier, name)) = input.split_once(char::is_whitespace) {
+        let weekday = parse_weekday(name.trim())?;
+        let offset = weekday.num_days_from_monday() as i64;
+        return match modifier {
+            "next" => Ok(today.week_start().add_days(7 + offset)),
+            "last" | "prev" => Ok(today.week_start().add_days(offset - 7)),
+            "this" => Ok(today.week_start().add_days(offset)),
+            _ => anyhow::bail!("unknown modifier '{}', use next, last or this", modifier),
+        };
+    }
+
+    if input.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        let weekday = parse_weekday(input)?;
+        let ahead = (weekday.num_days_from_monday() as i64
+            - today.weekday().num_days_from_monday() as i64)
+            .rem_euclid(7);
+        return Ok(today.add_days(ahead));
+    }
+
+    parse_absolute(input, today)
+}
+
+fn parse_offset(input: &str, today: AppDate) -> anyhow::Result<AppDate> {
+    let (sign, rest) = input.split_at(1);
+    let sign = if sign == "-" { -1 } else { 1 };
+
+    let (digits, unit) = match rest.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => rest.split_at(idx),
+        None => (rest, "d"),
+    };
+    let amount: i64 = digits
+        .parse()
+        .map_err(|_| anyhow::anyhow!("'{}' needs a number, e.g. +3 or -1w", input))?;
+
+    let too_large = || anyhow::anyhow!("offset '{}' is too large", input);
+    let amount = sign * amount;
+    match unit {
+        "d" => today.checked_add_days(amount).ok_or_else(too_large),
+        "w" => amount
+            .checked_mul(7)
+            .and_then(|days| today.checked_add_days(days))
+            .ok_or_else(too_large),
+        "m" => {
+            let months =
+                Months::new(u32::try_from(amount.unsigned_abs()).map_err(|_| too_large())?);
+            let shifted = if amount >= 0 {
+                today.0.checked_add_months(months)
+            } else {
+                today.0.checked_sub_months(months)
+            };
+            shifted.map(AppDate).ok_or_else(too_large)
+        }
+        _ => anyhow::bail!("unknown unit '{}', use d, w or m", unit),
+    }
+}
+
+fn parse_weekday(name: &str) -> anyhow::Result<Weekday> {
+    let weekday = match name {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => anyhow::bail!("unknown weekday '{}'", name),
+    };
+    Ok(weekday)
+}
+
+fn parse_absolute(input: &str, today: AppDate) -> anyhow::Result<AppDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(AppDate(date));
+    }
+
+    let parts: Vec<&str> = input.split(['.', '/']).collect();
+    let number = |part: &str| -> anyhow::Result<u32> {
+        part.parse()
+            .map_err(|_| anyhow::anyhow!("'{}' is not a date, try 25.12 or 2026-12-25", input))
+    };
+
+    let (day, month, year) = match parts.as_slice() {
+        [day, month] => (number(day)?, number(month)?, today.0.year()),
+        [day, month, year] => {
+            let year = number(year)?;
+            let year = if year < 100 { 2000 + year } else { year };
+            let year = i32::try_from(year).map_err(|_| unsupported_year(input))?;
+            (number(day)?, number(month)?, year)
+        }
+        _ => anyhow::bail!("'{}' is not a date, try 25.12 or 2026-12-25", input),
+    };
+
+    NaiveDate::from_ymd_opt(year, month, day)
+        .map(AppDate)
+        .ok_or_else(|| anyhow::anyhow!("{:02}.{:02}.{} does not exist", day, month, year))
 }
 
 impl From<NaiveDate> for AppDate {
@@ -0,0 +1,101 @@
+/// Scores how well `pattern` matches `text`, higher being better, or
+/// returns `None` when it does not match at all.
+///
+/// A pattern matches when its characters appear in the text in the same
+/// order, ignoring case, so `исп211` finds `ИСП-21-1`. `ё` and `е` are
+/// the same letter, and a pattern typed in the wrong keyboard layout
+/// (`bcg` for `исп`) is tried in the other one when it does not match.
+pub fn score(pattern: &str, text: &str) -> Option<i64> {
+    let text = fold(text);
+    score_folded(&fold(pattern), &text)
+        .or_else(|| score_folded(&fold(&swap_layout(pattern)), &text))
+}
+
+/// Indices of the items matching `pattern`, best matches first and in
+/// their original order otherwise. An empty pattern keeps every item.
+pub fn filter<T>(pattern: &str, items: &[T], text: impl Fn(&T) -> &str) -> Vec<usize> {
+    if pattern.trim().is_empty() {
+        return (0..items.len()).collect();
+    }
+
+    let mut scored: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| score(pattern, text(item)).map(|score| (score, i)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+fn score_folded(pattern: &[char], text: &[char]) -> Option<i64> {
+    let pattern: Vec<char> = pattern
+        .iter()
+        .copied()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut prev: Option<usize> = None;
+
+    for (i, &c) in text.iter().enumerate() {
+        if next == pattern.len() {
+            break;
+        }
+        if c != pattern[next] {
+            continue;
+        }
+
+        score += 1;
+        match prev {
+            Some(p) if p + 1 == i => score += 5,
+            Some(p) => score -= (i - p - 1).min(3) as i64,
+            None => {}
+        }
+        if i == 0 {
+            score += 5;
+        } else if !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        prev = Some(i);
+        next += 1;
+    }
+
+    if next < pattern.len() {
+        return None;
+    }
+
+    // Between equal matches, prefer the shorter name.
+    Some(score * 100 - text.len() as i64)
+}
+
+fn fold(s: &str) -> Vec<char> {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == 'ё' { 'е' } else { c })
+        .collect()
+}
+
+const QWERTY: &str = "qwertyuiop[]asdfghjkl;'zxcvbnm,.`";
+const JCUKEN: &str = "йцукенгшщзхъфывапролджэячсмитьбюё";
+
+/// Retypes `s` as if it was entered in the other of the QWERTY and
+/// ЙЦУКЕН layouts.
+fn swap_layout(s: &str) -> String {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| {
+            if let Some(i) = QWERTY.chars().position(|q| q == c) {
+                JCUKEN.chars().nth(i).unwrap_or(c)
+            } else if let Some(i) = JCUKEN.chars().position(|j| j == c) {
+                QWERTY.chars().nth(i).unwrap_or(c)
+            } else {
+                c
+            }
+        })
+        .collect()
+}
@@ -1,3 +1,7 @@
+pub mod clock;
 pub mod date;
+pub mod fuzzy;
+pub mod timeline;
 
+pub use clock::{Clock, Timezone};
 pub use date::AppDate;
@@ -0,0 +1,101 @@
+use chrono::{Duration, NaiveTime};
+use osars::models::Lesson;
+
+/// Where `now` falls within a day's lessons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LessonStatus {
+    /// Lesson `index` is running; `progress` is in `0.0..=1.0`.
+    Ongoing {
+        index: usize,
+        progress: f64,
+        remaining: Duration,
+        next: Option<usize>,
+    },
+    /// Between two lessons; lesson `next` starts after `starts_in`.
+    Break { next: usize, starts_in: Duration },
+    /// Before the first lesson of the day.
+    Upcoming { next: usize, starts_in: Duration },
+    /// Every lesson has ended.
+    Finished,
+}
+
+impl LessonStatus {
+    pub fn current(&self) -> Option<usize> {
+        match self {
+            LessonStatus::Ongoing { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> Option<usize> {
+        match self {
+            LessonStatus::Ongoing { next, .. } => *next,
+            LessonStatus::Break { next, .. } | LessonStatus::Upcoming { next, .. } => Some(*next),
+            LessonStatus::Finished => None,
+        }
+    }
+}
+
+/// Computes the status of `lessons` at `now`. Indices refer to positions
+/// in `lessons`, which need not be sorted. Returns `None` for an empty day.
+pub fn lesson_status(lessons: &[&Lesson], now: NaiveTime) -> Option<LessonStatus> {
+    if lessons.is_empty() {
+        return None;
+    }
+
+    let next = lessons
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.start_time > now)
+        .min_by_key(|(_, l)| l.start_time)
+        .map(|(i, _)| i);
+
+    let ongoing = lessons
+        .iter()
+        .position(|l| l.start_time <= now && now < l.end_time);
+
+    if let Some(index) = ongoing {
+        let lesson = lessons[index];
+        let total = (lesson.end_time - lesson.start_time).num_seconds().max(1);
+        let elapsed = (now - lesson.start_time).num_seconds();
+        return Some(LessonStatus::Ongoing {
+            index,
+            progress: (elapsed as f64 / total as f64).clamp(0.0, 1.0),
+            remaining: lesson.end_time - now,
+            next,
+        });
+    }
+
+    let Some(next) = next else {
+        return Some(LessonStatus::Finished);
+    };
+    let starts_in = lessons[next].start_time - now;
+
+    if lessons.iter().any(|l| l.end_time <= now) {
+        Some(LessonStatus::Break { next, starts_in })
+    } else {
+        Some(LessonStatus::Upcoming { next, starts_in })
+    }
+}
+
+/// Formats a countdown as `12 min` or `1 h 05 min`, rounding up so the
+/// last minute reads `1 min` rather than `0 min`.
+pub fn format_countdown(duration: Duration) -> String {
+    let minutes = (duration.num_seconds() + 59) / 60;
+    if minutes >= 60 {
+        format!("{} h {:02} min", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
+/// Formats how long ago something happened as `25 min ago`, `3 h ago` or
+/// `2 d ago`, using the largest whole unit.
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", seconds / 60),
+        3600..86400 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} d ago", seconds / 86400),
+    }
+}
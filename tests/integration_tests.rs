@@ -30,3 +30,1361 @@ fn test_app_date_formatting() {
     // Should contain "Today" or a date
     assert!(!formatted.is_empty());
 }
+
+#[test]
+fn test_app_date_week_start() {
+    let wednesday = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 21).unwrap());
+    let monday = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 19).unwrap());
+
+    assert_eq!(wednesday.week_start(), monday);
+    assert_eq!(monday.week_start(), monday);
+    assert_eq!(monday.add_days(6).week_start(), monday);
+    assert_eq!(monday.add_days(7).week_start(), monday.add_days(7));
+}
+
+#[test]
+fn test_app_date_months() {
+    let date = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 1, 31).unwrap());
+
+    assert_eq!(date.month_start().day(), 1);
+    assert_eq!(date.month_days().len(), 31);
+    assert_eq!(date.add_months(1).month_days().len(), 28);
+    assert_eq!(date.add_months(1).day(), 28);
+    assert!(date.add_months(-1).same_month(&date.add_days(-31)));
+}
+
+#[test]
+fn test_app_date_parse() {
+    // Wednesday
+    let today = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 21).unwrap());
+    let date = |y, m, d| AppDate::from(chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap());
+
+    assert_eq!(AppDate::parse("25.12", today).unwrap(), date(2026, 12, 25));
+    assert_eq!(
+        AppDate::parse("25.12.27", today).unwrap(),
+        date(2027, 12, 25)
+    );
+    assert_eq!(
+        AppDate::parse("2026-12-25", today).unwrap(),
+        date(2026, 12, 25)
+    );
+    assert_eq!(AppDate::parse("+3", today).unwrap(), date(2026, 10, 24));
+    assert_eq!(AppDate::parse("-1w", today).unwrap(), date(2026, 10, 14));
+    assert_eq!(AppDate::parse("mon", today).unwrap(), date(2026, 10, 26));
+    assert_eq!(AppDate::parse("Friday", today).unwrap(), date(2026, 10, 23));
+    assert_eq!(AppDate::parse("wed", today).unwrap(), today);
+    assert_eq!(
+        AppDate::parse("next tue", today).unwrap(),
+        date(2026, 10, 27)
+    );
+    assert_eq!(
+        AppDate::parse("last fri", today).unwrap(),
+        date(2026, 10, 16)
+    );
+
+    assert!(AppDate::parse("31.02", today).is_err());
+    assert!(AppDate::parse("+3y", today).is_err());
+    assert!(AppDate::parse("someday", today).is_err());
+    assert!(AppDate::parse("", today).is_err());
+}
+
+#[test]
+fn test_app_date_parse_out_of_range() {
+    let today = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 21).unwrap());
+
+    for input in [
+        "+100000000",
+        "-100000000",
+        "+9223372036854775807",
+        "+9999999999999w",
+        "+100000000m",
+        "-5000000000m",
+    ] {
+        let error = AppDate::parse(input, today).unwrap_err();
+        assert!(
+            error.to_string().contains("too large"),
+            "{}: {}",
+            input,
+            error
+        );
+    }
+    assert!(AppDate::parse("+1000m", today).is_ok());
+
+    // Dates far from today are rejected before navigating from them can
+    // run past the range of dates.
+    for input in [
+        "1.1.4294967295",
+        "1.1.262142",
+        "1.1.10000",
+        "+90000000",
+        "31.12.1899",
+        "-50000000",
+    ] {
+        let error = AppDate::parse(input, today).unwrap_err();
+        assert!(
+            error.to_string().contains("supported years"),
+            "{}: {}",
+            input,
+            error
+        );
+    }
+    for input in ["1.1.1900", "31.12.9999"] {
+        assert!(AppDate::parse(input, today).is_ok(), "{}", input);
+    }
+}
+
+#[test]
+fn test_timezone_parsing() {
+    use osatui::utils::Timezone;
+
+    assert_eq!("local".parse::<Timezone>().unwrap(), Timezone::Local);
+    assert_eq!("".parse::<Timezone>().unwrap(), Timezone::Local);
+    assert!(matches!(
+        "Asia/Yekaterinburg".parse::<Timezone>().unwrap(),
+        Timezone::Named(_)
+    ));
+
+    let plus_five = chrono::FixedOffset::east_opt(5 * 3600).unwrap();
+    assert_eq!(
+        "+05:00".parse::<Timezone>().unwrap(),
+        Timezone::Fixed(plus_five)
+    );
+    assert_eq!(
+        "UTC+5".parse::<Timezone>().unwrap(),
+        Timezone::Fixed(plus_five)
+    );
+
+    assert!("Mars/Olympus".parse::<Timezone>().is_err());
+    assert!("+25:00".parse::<Timezone>().is_err());
+}
+
+#[test]
+fn test_clock_today_respects_timezone() {
+    use chrono::TimeZone;
+    use osatui::utils::{Clock, Timezone};
+
+    // 02:30 in Tyumen is still the previous day in UTC.
+    let at = chrono::Utc
+        .with_ymd_and_hms(2026, 10, 20, 21, 30, 0)
+        .unwrap();
+    let tyumen = Clock::fixed(at, "Asia/Yekaterinburg".parse().unwrap());
+    let utc = Clock::fixed(at, "UTC".parse().unwrap());
+
+    let date = |y, m, d| AppDate::from(chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap());
+    assert_eq!(tyumen.today(), date(2026, 10, 21));
+    assert_eq!(utc.today(), date(2026, 10, 20));
+    assert_eq!(tyumen.now().format("%H:%M").to_string(), "02:30");
+    assert_eq!(
+        tyumen.timezone(),
+        Timezone::Named(chrono_tz::Asia::Yekaterinburg)
+    );
+}
+
+#[test]
+fn test_app_date_format_uses_pinned_clock() {
+    use chrono::TimeZone;
+    use osatui::utils::clock::Clock;
+
+    // Tested through a clock value: the process-wide clock is shared with
+    // the tests running in parallel.
+    let at = chrono::Utc
+        .with_ymd_and_hms(2026, 10, 20, 21, 30, 0)
+        .unwrap();
+    let today = Clock::fixed(at, "+05:00".parse().unwrap()).today();
+
+    let date = |y, m, d| AppDate::from(chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap());
+    assert_eq!(today, date(2026, 10, 21));
+    assert_eq!(date(2026, 10, 21).format_relative_to(today), "Today");
+    assert_eq!(date(2026, 10, 20).format_relative_to(today), "Yesterday");
+    assert_eq!(date(2026, 10, 22).format_relative_to(today), "Tomorrow");
+    assert_eq!(
+        date(2026, 10, 25).format_relative_to(today),
+        "25 October 2026"
+    );
+}
+
+#[test]
+fn test_lesson_status() {
+    use chrono::{Duration, NaiveTime};
+    use osars::models::Lesson;
+    use osatui::utils::timeline::{LessonStatus, format_age, format_countdown, lesson_status};
+
+    let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    let lesson = |title: &str, start, end| Lesson {
+        title: title.to_string(),
+        cabinet: "101".to_string(),
+        teacher: "Teacher".to_string(),
+        order: 0,
+        start_time: start,
+        end_time: end,
+    };
+    let first = lesson("Math", time(8, 30), time(10, 0));
+    let second = lesson("Physics", time(10, 10), time(11, 40));
+    let lessons = [&first, &second];
+
+    assert_eq!(
+        lesson_status(&lessons, time(8, 18)),
+        Some(LessonStatus::Upcoming {
+            next: 0,
+            starts_in: Duration::minutes(12)
+        })
+    );
+    assert_eq!(
+        lesson_status(&lessons, time(9, 15)),
+        Some(LessonStatus::Ongoing {
+            index: 0,
+            progress: 0.5,
+            remaining: Duration::minutes(45),
+            next: Some(1)
+        })
+    );
+    assert_eq!(
+        lesson_status(&lessons, time(10, 5)),
+        Some(LessonStatus::Break {
+            next: 1,
+            starts_in: Duration::minutes(5)
+        })
+    );
+    assert_eq!(
+        lesson_status(&lessons, time(12, 0)),
+        Some(LessonStatus::Finished)
+    );
+    assert_eq!(lesson_status(&[], time(12, 0)), None);
+
+    assert_eq!(format_countdown(Duration::seconds(30)), "1 min");
+    assert_eq!(format_countdown(Duration::minutes(65)), "1 h 05 min");
+    assert_eq!(format_age(25 * 60), "25 min ago");
+    assert_eq!(format_age(3 * 3600 + 59), "3 h ago");
+    assert_eq!(format_age(2 * 86400), "2 d ago");
+}
+
+#[test]
+fn test_api_error_classification() {
+    use osatui::api::ApiError;
+
+    let not_found = ApiError::from(osars::Error::NotFound("group".to_string()));
+    assert!(matches!(not_found, ApiError::Http { status: 404, .. }));
+    assert!(!not_found.is_network());
+
+    let server = ApiError::from(osars::Error::Api {
+        status_code: 502,
+        message: "Bad Gateway".to_string(),
+    });
+    assert!(matches!(server, ApiError::Http { status: 502, .. }));
+
+    let decode_error = serde_json::from_str::<Vec<u32>>("{").unwrap_err();
+    let decode = ApiError::from(osars::Error::Serialization(decode_error));
+    assert!(matches!(decode, ApiError::Decode(_)));
+
+    assert!(ApiError::Timeout.is_network());
+    assert!(ApiError::Offline("connection refused".to_string()).is_network());
+}
+
+#[test]
+fn test_provider_slug() {
+    use osatui::api::cache::provider_slug;
+
+    let tyumen = provider_slug("https://api.thisishyum.ru/schedule_api/tyumen");
+    assert!(tyumen.starts_with("api-thisishyum-ru-schedule-api-tyumen-"));
+    assert_eq!(
+        tyumen,
+        provider_slug("https://api.thisishyum.ru/schedule_api/tyumen/")
+    );
+    assert_ne!(
+        tyumen,
+        provider_slug("https://api.thisishyum.ru/schedule_api/omsk")
+    );
+    // Same readable part, different scheme: still distinct namespaces.
+    assert_ne!(
+        provider_slug("http://example.com"),
+        provider_slug("https://example.com")
+    );
+}
+
+/// Sets the modification time of `path` to `secs` seconds ago, making a
+/// cache entry that old.
+fn age_file(path: &std::path::Path, secs: u64) {
+    let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(secs);
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_cache_keeps_stale_entries_for_offline_use() {
+    use osatui::api::{
+        ApiClient,
+        cache::{CacheManager, provider_slug},
+    };
+    use osatui::config::DEFAULT_PROFILE;
+
+    let dir = tempfile::tempdir().unwrap();
+    let date = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 21).unwrap());
+    let mut config = Config::default();
+    // Nothing listens there, so every request fails as offline.
+    config.set_api_url("http://127.0.0.1:9".to_string());
+    let path = dir
+        .path()
+        .join(provider_slug(config.api_url()))
+        .join("college-1")
+        .join(format!("1-{}.json", date.iso()));
+    let mut cache = CacheManager::new(
+        dir.path().to_path_buf(),
+        config.cache_ttl(),
+        config.max_stale_age(),
+    )
+    .await
+    .unwrap();
+    cache.set_group_id(config.group_id());
+    cache.set_namespace(DEFAULT_PROFILE, config.api_url(), Some(config.college_id()));
+    cache.set(&date, &[]).await.unwrap();
+    assert!(cache.get(&date).await.unwrap().is_some());
+
+    // A fresh entry is served together with the time since it was fetched.
+    age_file(&path, 600);
+    let api = ApiClient::with_cache_dir(config.clone(), dir.path().to_path_buf())
+        .await
+        .unwrap();
+    let fetched = api.fetch(&date).await.unwrap();
+    assert!((600..700).contains(&fetched.age), "{}", fetched.age);
+    assert!(fetched.stale_age.is_none());
+
+    // Past the TTL the entry is kept, but only served as stale.
+    let age = config.cache_ttl() + 600;
+    age_file(&path, age);
+    assert!(cache.get(&date).await.unwrap().is_none());
+    let (_, stale_age) = cache.get_stale(&date).await.unwrap().unwrap();
+    assert!((age..age + 100).contains(&stale_age), "{}", stale_age);
+    assert!(path.exists());
+
+    // Fetching falls back to it when the API cannot be reached.
+    let fetched = api.fetch(&date).await.unwrap();
+    assert!(fetched.schedules.is_empty());
+    assert!(fetched.stale_age.is_some_and(|stale_age| stale_age >= age));
+
+    // A maximum stale age of 0 turns the fallback off.
+    let mut no_stale = cache.clone();
+    no_stale.update_ttl(config.cache_ttl(), 0);
+    assert!(no_stale.get_stale(&date).await.unwrap().is_none());
+
+    // Entries older than the maximum stale age are dropped.
+    age_file(&path, config.max_stale_age() + 600);
+    assert!(cache.get_stale(&date).await.unwrap().is_none());
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_cache_migration() {
+    use osatui::api::cache::{CacheManager, provider_slug};
+    use osatui::config::DEFAULT_PROFILE;
+
+    let root = tempfile::tempdir().unwrap();
+    let flat = ["162-25-12-2026.json", "162-26-12-2026.json"];
+    for name in flat {
+        std::fs::write(root.path().join(name), "[]").unwrap();
+    }
+
+    let url = "https://api.thisishyum.ru/schedule_api/tyumen";
+    let mut cache = CacheManager::new(root.path().to_path_buf(), 3600, 3600)
+        .await
+        .unwrap();
+    cache.set_namespace(DEFAULT_PROFILE, url, Some(1));
+    cache.migrate_flat_files().await.unwrap();
+
+    let college = root.path().join(provider_slug(url)).join("college-1");
+    for name in flat {
+        assert!(!root.path().join(name).exists());
+        assert!(college.join(name).exists());
+    }
+
+    // Another college of the same provider finds nothing left to move.
+    cache.set_namespace(DEFAULT_PROFILE, url, Some(2));
+    cache.migrate_flat_files().await.unwrap();
+    assert!(
+        !root
+            .path()
+            .join(provider_slug(url))
+            .join("college-2")
+            .exists()
+    );
+    for name in flat {
+        assert!(college.join(name).exists());
+    }
+}
+
+/// Serves `null`, an empty day, to every request on a local port and
+/// records the requested paths.
+async fn empty_day_server() -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = paths.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                if let Some(path) = request.split_whitespace().nth(1) {
+                    recorded.lock().unwrap().push(path.to_string());
+                }
+                let response = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnull";
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    (url, paths)
+}
+
+#[tokio::test]
+async fn test_prefetch_window() {
+    use osatui::api::{ApiClient, cache::CacheManager};
+    use osatui::config::DEFAULT_PROFILE;
+
+    let (url, paths) = empty_day_server().await;
+    let root = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.set_api_url(url.clone());
+    assert_eq!(config.prefetch_window(), (2, 7));
+
+    let center = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 21).unwrap());
+    // A fresh entry in the window is served from the cache.
+    let mut cache = CacheManager::new(
+        root.path().to_path_buf(),
+        config.cache_ttl(),
+        config.max_stale_age(),
+    )
+    .await
+    .unwrap();
+    cache.set_namespace(DEFAULT_PROFILE, &url, Some(config.college_id()));
+    cache.set_group_id(config.group_id());
+    cache.set(&center.next(), &[]).await.unwrap();
+
+    let api = ApiClient::with_cache_dir(config, root.path().to_path_buf())
+        .await
+        .unwrap();
+    api.prefetch(center).await;
+
+    let mut requested = paths.lock().unwrap().clone();
+    requested.sort();
+    let mut expected: Vec<String> = [-2, -1, 2, 3, 4, 5, 6, 7]
+        .into_iter()
+        .map(|offset| format!("/groups/1/schedules?date={}", center.add_days(offset).iso()))
+        .collect();
+    expected.sort();
+    assert_eq!(requested, expected);
+
+    // The prefetched days are cached now, so nothing is requested again.
+    api.prefetch(center).await;
+    assert_eq!(paths.lock().unwrap().len(), 8);
+}
+
+#[tokio::test]
+async fn test_cache_migration_leaves_foreign_files() {
+    use osatui::api::cache::{CacheManager, provider_slug};
+    use osatui::config::DEFAULT_PROFILE;
+
+    // A cache root shared with other programs, as with
+    // `OSATUI_CACHE_DIR=~/.cache`.
+    let root = tempfile::tempdir().unwrap();
+    let foreign = [
+        "settings.json",
+        "12-notes.json",
+        "162-31-02-2026.json",
+        "162-25-12-2026.txt",
+    ];
+    for name in foreign {
+        std::fs::write(root.path().join(name), "{}").unwrap();
+    }
+    std::fs::write(root.path().join("162-25-12-2026.json"), "[]").unwrap();
+
+    let url = "https://example.com";
+    let mut cache = CacheManager::new(root.path().to_path_buf(), 3600, 3600)
+        .await
+        .unwrap();
+    cache.set_namespace(DEFAULT_PROFILE, url, Some(1));
+    cache.migrate_flat_files().await.unwrap();
+
+    for name in foreign {
+        assert!(root.path().join(name).exists(), "{} was moved", name);
+    }
+    let college = root.path().join(provider_slug(url)).join("college-1");
+    assert!(college.join("162-25-12-2026.json").exists());
+}
+
+#[test]
+fn test_cli_output_formats() {
+    use chrono::NaiveTime;
+    use osars::models::{Lesson, Schedule};
+    use osatui::cli::{OutputFormat, format::render};
+
+    let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    let lesson = |order, title: &str, start, end| Lesson {
+        title: title.to_string(),
+        cabinet: "101".to_string(),
+        teacher: "Ivanov, I.".to_string(),
+        order,
+        start_time: start,
+        end_time: end,
+    };
+    let date = AppDate::from(chrono::NaiveDate::from_ymd_opt(2026, 10, 20).unwrap());
+    let days = vec![(
+        date,
+        vec![Schedule {
+            group_id: 161,
+            date: date.into(),
+            lessons: vec![
+                lesson(2, "Physics", time(10, 10), time(11, 40)),
+                lesson(1, "Math", time(8, 30), time(10, 0)),
+            ],
+        }],
+    )];
+
+    let plain = render(&days, OutputFormat::Plain).unwrap();
+    assert_eq!(
+        plain,
+        "08:30-10:00 Math (101, Ivanov, I.)\n10:10-11:40 Physics (101, Ivanov, I.)\n"
+    );
+
+    let csv = render(&days, OutputFormat::Csv).unwrap();
+    assert_eq!(
+        csv.lines().nth(1),
+        Some("2026-10-20,1,08:30,10:00,Math,101,\"Ivanov, I.\"")
+    );
+
+    let table = render(&days, OutputFormat::Table).unwrap();
+    assert!(table.starts_with("Tuesday 20.10.2026\n#  Time"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&render(&days, OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(json[0]["date"], "2026-10-20");
+    assert_eq!(json[0]["lessons"][0]["title"], "Math");
+}
+
+#[tokio::test]
+async fn test_config_overrides_are_not_saved() {
+    use osatui::config::Overrides;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let overrides = Overrides {
+        group_id: Some(161),
+        api_url: Some("https://example.com/api".to_string()),
+        cache_enabled: Some(false),
+        ..Overrides::default()
+    };
+
+    let mut config = Config::load_from(path.clone(), Overrides::default(), overrides)
+        .await
+        .unwrap();
+    assert_eq!(config.group_id(), 161);
+    assert_eq!(config.api_url(), "https://example.com/api");
+    assert!(!config.cache_enabled());
+
+    config.set_campus(7);
+    config.save().await.unwrap();
+
+    let saved = Config::load_from(path.clone(), Overrides::default(), Overrides::default())
+        .await
+        .unwrap();
+    assert_eq!(saved.group_id(), 1);
+    assert_eq!(saved.campus_id(), 7);
+    assert_eq!(saved.api_url(), Config::default().api_url());
+    assert!(saved.cache_enabled());
+
+    // Choosing a group in the app replaces the override and is saved.
+    config.set_group(42);
+    assert_eq!(config.group_id(), 42);
+}
+
+#[tokio::test]
+async fn test_config_layers() {
+    use osatui::config::{Layer, Overrides};
+    use std::collections::HashMap;
+
+    let vars = HashMap::from([
+        ("OSATUI_API_URL", "https://env.example.com/"),
+        ("OSATUI_GROUP_ID", "161"),
+        ("OSATUI_CACHE_DIR", "/tmp/osatui-cache"),
+        ("OSATUI_THEME", ""),
+    ]);
+    let env = Overrides::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+    assert_eq!(env.api_url.as_deref(), Some("https://env.example.com"));
+    assert_eq!(env.theme, None);
+
+    let bad = Overrides::from_vars(|name| (name == "OSATUI_GROUP_ID").then(|| "abc".to_string()));
+    assert!(bad.unwrap_err().to_string().contains("OSATUI_GROUP_ID"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(
+        &path,
+        "[api]\nurl = \"https://file.example.com\"\ncollege_id = 2\ncampus_id = 3\ngroup_id = 4\n\n\
+         [app]\nrefresh_interval = 60\ncache_enabled = true\ncache_ttl = 10\ncurrent_theme = \"dark\"\n\n[keymap]\n",
+    )
+    .unwrap();
+    let cli = Overrides {
+        group_id: Some(999),
+        ..Overrides::default()
+    };
+
+    let config = Config::load_from(path, env, cli).await.unwrap();
+    assert_eq!(config.api_url(), "https://env.example.com");
+    assert_eq!(config.group_id(), 999);
+    assert_eq!(config.college_id(), 2);
+    assert_eq!(
+        config.cache_dir(),
+        std::path::PathBuf::from("/tmp/osatui-cache")
+    );
+
+    let layer = |key: &str| {
+        config
+            .effective()
+            .into_iter()
+            .find(|s| s.key == key)
+            .unwrap()
+            .layer
+    };
+    assert_eq!(layer("api.group_id"), Layer::Cli);
+    assert_eq!(layer("api.url"), Layer::Env);
+    assert_eq!(layer("app.cache_ttl"), Layer::File);
+    assert_eq!(layer("app.timezone"), Layer::Default);
+}
+
+#[tokio::test]
+async fn test_config_profiles() {
+    use osatui::config::Overrides;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let mut toml = toml::to_string(&Config::default()).unwrap();
+    toml.push_str(
+        "\n[profiles.sibling]\nurl = \"https://example.com\"\ncollege_id = 2\ncampus_id = 3\ngroup_id = 162\n",
+    );
+    std::fs::write(&path, toml).unwrap();
+
+    let mut config = Config::load_from(path.clone(), Overrides::default(), Overrides::default())
+        .await
+        .unwrap();
+    assert_eq!(config.profile_names(), ["default", "sibling"]);
+    assert_eq!(config.active_profile(), "default");
+    assert_eq!(config.group_id(), 1);
+    assert!(config.set_active_profile("teacher").is_err());
+
+    let shown = chrono::NaiveDate::from_ymd_opt(2026, 10, 20).unwrap();
+    config.set_last_date(shown);
+    config.set_active_profile("sibling").unwrap();
+    assert_eq!(config.group_id(), 162);
+    assert_eq!(config.api_url(), "https://example.com");
+    assert_eq!(config.last_date(), None);
+
+    config.set_group(163);
+    config.save().await.unwrap();
+
+    let saved = Config::load_from(path.clone(), Overrides::default(), Overrides::default())
+        .await
+        .unwrap();
+    assert_eq!(saved.active_profile(), "sibling");
+    assert_eq!(saved.group_id(), 163);
+
+    let cli = Overrides {
+        profile: Some("default".to_string()),
+        ..Overrides::default()
+    };
+    let default = Config::load_from(path.clone(), Overrides::default(), cli)
+        .await
+        .unwrap();
+    assert_eq!(default.group_id(), 1);
+    assert_eq!(default.last_date(), Some(shown));
+
+    // Overrides of the API settings do not follow into another profile,
+    // whose values are reported under its own section.
+    let cli = Overrides {
+        profile: Some("default".to_string()),
+        group_id: Some(161),
+        ..Overrides::default()
+    };
+    let mut switched = Config::load_from(path.clone(), Overrides::default(), cli)
+        .await
+        .unwrap();
+    assert_eq!(switched.group_id(), 161);
+    switched.set_active_profile("sibling").unwrap();
+    assert_eq!(switched.group_id(), 163);
+    let setting = switched
+        .effective()
+        .into_iter()
+        .find(|s| s.key == "profiles.sibling.group_id")
+        .unwrap();
+    assert_eq!(setting.value, "163");
+    assert_eq!(setting.layer, osatui::config::Layer::File);
+    assert!(
+        switched
+            .effective()
+            .iter()
+            .all(|s| !s.key.starts_with("api."))
+    );
+
+    let unknown = Overrides {
+        profile: Some("teacher".to_string()),
+        ..Overrides::default()
+    };
+    assert!(
+        Config::load_from(path, Overrides::default(), unknown)
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_keymap_modifiers() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use osatui::{config::keymap::KeyMap, ui::screens::normal::format_key};
+
+    let keymap: KeyMap = toml::from_str(
+        r#"
+        selector = "C-o"
+        settings = "Ctrl+Alt+s"
+        exit = { key = "q", ctrl = true }
+        week_view = "W"
+        "#,
+    )
+    .unwrap();
+    let event = |code, modifiers| [KeyEvent::new(code, modifiers)];
+
+    assert!(
+        keymap
+            .normal()
+            .selector()
+            .matches(&event(KeyCode::Char('o'), KeyModifiers::CONTROL))
+    );
+    assert!(
+        !keymap
+            .normal()
+            .selector()
+            .matches(&event(KeyCode::Char('o'), KeyModifiers::NONE))
+    );
+    assert!(keymap.normal().settings().matches(&event(
+        KeyCode::Char('s'),
+        KeyModifiers::CONTROL | KeyModifiers::ALT
+    )));
+    assert!(
+        keymap
+            .normal()
+            .exit()
+            .matches(&event(KeyCode::Char('q'), KeyModifiers::CONTROL))
+    );
+
+    // Shift+letter arrives differently depending on the terminal.
+    assert!(
+        keymap
+            .normal()
+            .week_view()
+            .matches(&event(KeyCode::Char('W'), KeyModifiers::SHIFT))
+    );
+    assert!(
+        keymap
+            .normal()
+            .week_view()
+            .matches(&event(KeyCode::Char('W'), KeyModifiers::NONE))
+    );
+    assert!(
+        !keymap
+            .normal()
+            .week_view()
+            .matches(&event(KeyCode::Char('w'), KeyModifiers::NONE))
+    );
+    // Unset entries keep their defaults.
+    assert!(
+        keymap
+            .normal()
+            .prev_day()
+            .matches(&event(KeyCode::Left, KeyModifiers::NONE))
+    );
+
+    assert_eq!(format_key(&keymap.normal().selector()), "Ctrl+O");
+    assert_eq!(format_key(&keymap.normal().settings()), "Ctrl+Alt+S");
+    assert_eq!(format_key(&keymap.normal().week_view()), "Shift+W");
+    assert_eq!(format_key(&keymap.normal().prev_day()), "←");
+    assert_eq!(format_key(&keymap.normal().calendar()), "c");
+}
+
+#[test]
+fn test_keymap_sections() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use osatui::{config::keymap::KeyMap, ui::screens::normal::help_text};
+
+    let keymap: KeyMap = toml::from_str(
+        r#"
+        exit = "x"
+
+        [normal]
+        exit = "Q"
+        reload = "C-r"
+
+        [selector]
+        up = ["Up", "k"]
+        down = ["Down", "j"]
+
+        [week]
+        back = "Backspace"
+
+        [setup]
+        save = "C-s"
+        "#,
+    )
+    .unwrap();
+    let key = |code| [KeyEvent::new(code, KeyModifiers::NONE)];
+
+    // `[keymap.normal]` wins over the flat entries of older configs.
+    assert!(keymap.normal().exit().matches(&key(KeyCode::Char('Q'))));
+    assert!(!keymap.normal().exit().matches(&key(KeyCode::Char('x'))));
+    assert!(
+        keymap
+            .normal()
+            .reload()
+            .matches(&[KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)])
+    );
+
+    let selector = keymap.selector();
+    assert!(selector.up().matches(&key(KeyCode::Up)));
+    assert!(selector.up().matches(&key(KeyCode::Char('k'))));
+    assert!(selector.down().matches(&key(KeyCode::Char('j'))));
+    assert!(selector.select().matches(&key(KeyCode::Enter)));
+    assert!(!keymap.setup().save().matches(&key(KeyCode::Enter)));
+    assert!(keymap.week().back().matches(&key(KeyCode::Backspace)));
+    assert!(!keymap.week().back().matches(&key(KeyCode::Esc)));
+    assert!(
+        keymap
+            .calendar()
+            .prev_month()
+            .matches(&key(KeyCode::PageUp))
+    );
+
+    assert_eq!(
+        help_text(&[(selector.up(), "up"), (selector.cancel(), "cancel")]),
+        "↑/k: up | Esc: cancel"
+    );
+
+    // The old `selector`/`calendar` keys are still read as day view keys.
+    let legacy: KeyMap = toml::from_str("selector = \"C-o\"\ncalendar = \"m\"").unwrap();
+    assert!(legacy.normal().calendar().matches(&key(KeyCode::Char('m'))));
+    assert!(legacy.selector().up().matches(&key(KeyCode::Up)));
+    let saved = toml::to_string(&legacy).unwrap();
+    let reloaded: KeyMap = toml::from_str(&saved).unwrap();
+    assert_eq!(reloaded.normal().selector(), legacy.normal().selector());
+
+    assert!(toml::from_str::<KeyMap>("[selector]\nupp = \"k\"").is_err());
+}
+
+#[test]
+fn test_keymap_presets() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use osatui::{
+        config::keymap::{KeyMap, Preset},
+        ui::screens::normal::format_key,
+    };
+
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+    let vim: KeyMap = toml::from_str(
+        r#"
+        preset = "vim"
+
+        [normal]
+        next_day = "n"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(vim.preset(), Preset::Vim);
+    assert!(vim.normal().prev_day().matches(&[key('h')]));
+    assert!(vim.normal().goto_date().matches(&[key('/')]));
+    // Entries of the file replace the preset's keys for that action.
+    assert!(vim.normal().next_day().matches(&[key('n')]));
+    assert!(!vim.normal().next_day().matches(&[key('l')]));
+    // Sections the file leaves alone still come from the preset.
+    assert!(vim.selector().down().matches(&[key('j')]));
+
+    let first = vim.selector().first();
+    assert!(first.continues(&[key('g')]));
+    assert!(!first.matches(&[key('g')]));
+    assert!(first.matches(&[key('g'), key('g')]));
+    assert!(!first.continues(&[key('g'), key('g')]));
+    assert!(vim.selector().last().matches(&[key('G')]));
+    assert_eq!(format_key(&first), "Home/gg");
+
+    let emacs: KeyMap = toml::from_str("preset = \"emacs\"").unwrap();
+    let exit = emacs.normal().exit();
+    assert!(exit.matches(&[ctrl('x'), ctrl('c')]));
+    assert!(exit.continues(&[ctrl('x')]));
+    assert!(emacs.selector().down().matches(&[ctrl('n')]));
+    assert!(emacs.selector().up().matches(&[ctrl('p')]));
+    assert_eq!(format_key(&exit), "q/Ctrl+X Ctrl+C");
+
+    // The preset is kept when the config is saved, the expanded keys are not.
+    let saved = toml::to_string(&vim).unwrap();
+    assert!(saved.contains("preset = \"vim\""));
+    assert!(!saved.contains("prev_day"));
+    assert!(KeyMap::default().normal().goto_date().matches(&[key('g')]));
+}
+
+#[test]
+fn test_keymap_validation() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use osatui::config::keymap::KeyMap;
+
+    let key = |code| [KeyEvent::new(code, KeyModifiers::NONE)];
+
+    let keymap: KeyMap = toml::from_str(
+        r#"
+        [normal]
+        prev_day = "pgup"
+        next_day = "PageDown"
+        cur_day = "F24"
+        goto_date = "Space"
+        reload = "C-minus"
+        settings = "Delete"
+        profiles = "question"
+        "#,
+    )
+    .unwrap();
+    let normal = keymap.normal();
+    assert!(normal.prev_day().matches(&key(KeyCode::PageUp)));
+    assert!(normal.next_day().matches(&key(KeyCode::PageDown)));
+    assert!(normal.cur_day().matches(&key(KeyCode::F(24))));
+    assert!(normal.goto_date().matches(&key(KeyCode::Char(' '))));
+    assert!(
+        normal
+            .reload()
+            .matches(&[KeyEvent::new(KeyCode::Char('-'), KeyModifiers::CONTROL)])
+    );
+    assert!(normal.settings().matches(&key(KeyCode::Delete)));
+    assert!(normal.profiles().matches(&key(KeyCode::Char('?'))));
+
+    let error = |text| toml::from_str::<KeyMap>(text).unwrap_err().to_string();
+
+    let unknown = error("[normal]\nprev_day = [\"Left\", \"Lft\"]");
+    assert!(unknown.contains("keymap.normal.prev_day"), "{}", unknown);
+    assert!(unknown.contains("'Lft'"), "{}", unknown);
+    assert!(error("[setup]\nsave = \"F25\"").contains("keymap.setup.save"));
+    assert!(error("[selector]\nup = \"Hyper-k\"").contains("'Hyper-k'"));
+
+    let conflict = error("[normal]\nexit = \"w\"\nreload = [\"R\", \"w\"]");
+    assert!(conflict.contains("keymap.normal.exit"), "{}", conflict);
+    assert!(conflict.contains("keymap.normal.reload"), "{}", conflict);
+    // An entry may take a default key, which the other action then loses,
+    // as in `settings = "c"` written before `c` opened the calendar.
+    let shadowed: KeyMap = toml::from_str("settings = \"c\"").unwrap();
+    assert!(
+        shadowed
+            .normal()
+            .settings()
+            .matches(&key(KeyCode::Char('c')))
+    );
+    assert!(shadowed.normal().calendar().is_empty());
+    let shadowed: KeyMap = toml::from_str("[selector]\nfilter = \"Tab\"").unwrap();
+    assert!(shadowed.selector().find_group().is_empty());
+    // A key that starts another action's sequence hides that sequence.
+    let prefix = error("[selector]\nfirst = \"g g\"\ncancel = \"g\"");
+    assert!(prefix.contains("keymap.selector.first"), "{}", prefix);
+    // Preset keys give way to entries like default keys do.
+    let vim: KeyMap = toml::from_str("preset = \"vim\"\n[selector]\ncancel = \"g\"").unwrap();
+    assert!(vim.selector().cancel().matches(&key(KeyCode::Char('g'))));
+    assert!(!vim.selector().first().continues(&key(KeyCode::Char('g'))));
+    assert!(vim.selector().first().matches(&key(KeyCode::Home)));
+    let vim: KeyMap = toml::from_str("preset = \"vim\"\n[normal]\nexit = \"h\"").unwrap();
+    assert!(vim.normal().exit().matches(&key(KeyCode::Char('h'))));
+    assert!(!vim.normal().prev_day().matches(&key(KeyCode::Char('h'))));
+    assert!(vim.normal().prev_day().matches(&key(KeyCode::Left)));
+    // Actions of different modes may share keys.
+    assert!(toml::from_str::<KeyMap>("[selector]\nselect = \"o\"").is_ok());
+}
+
+#[test]
+fn test_fuzzy_filter() {
+    use osatui::{app::AppState, ui::screens::selector::SelectionStage, utils::fuzzy};
+
+    assert!(fuzzy::score("исп211", "ИСП-21-1").is_some());
+    assert!(fuzzy::score("ИСП", "исп-21-1").is_some());
+    assert!(fuzzy::score("ёлка", "Елка").is_some());
+    assert!(fuzzy::score("bcg", "ИСП-21-1").is_some(), "wrong layout");
+    assert!(fuzzy::score("исп3", "ИСП-21-1").is_none());
+    assert!(fuzzy::score("", "anything").is_some());
+    // Consecutive and word-start matches rank higher.
+    assert!(fuzzy::score("ис", "ИС-22").unwrap() > fuzzy::score("ис", "ИКС-22").unwrap());
+
+    let names = ["ПКС-22-1", "ИСП-21-1", "ИСП-21-2", "Экономика"];
+    assert_eq!(fuzzy::filter("", &names, |n| n), vec![0, 1, 2, 3]);
+    assert_eq!(fuzzy::filter("исп", &names, |n| n), vec![1, 2]);
+    assert_eq!(fuzzy::filter("исп2", &names, |n| n), vec![1, 2]);
+    assert_eq!(fuzzy::filter("исп212", &names, |n| n), vec![2]);
+
+    let mut state = AppState::new(Config::default());
+    state.enter_selector(SelectionStage::Group);
+    state.page_size = 2;
+    state.set_groups(
+        (1..=5)
+            .map(|i| osars::models::Group {
+                id: i,
+                name: format!("{}-{}", if i % 2 == 0 { "ПКС" } else { "ИСП" }, i),
+                campus_id: 1,
+            })
+            .collect(),
+    );
+    assert_eq!(state.page_items(), &[0, 1]);
+
+    for c in "исп".chars() {
+        state.push_filter(c);
+    }
+    assert_eq!(state.filtered, vec![0, 2, 4]);
+    assert_eq!(state.page_items(), &[0, 2]);
+    state.next_item();
+    state.next_item();
+    assert_eq!(state.page, 1);
+    assert_eq!(state.get_selected_group().unwrap().id, 5);
+    state.next_item();
+    assert_eq!(state.get_selected_group().unwrap().id, 5);
+
+    state.pop_filter();
+    assert_eq!(state.page, 0);
+    state.clear_filter();
+    assert_eq!(state.filtered.len(), 5);
+    assert!(!state.filter_focused);
+}
+
+#[tokio::test]
+async fn test_get_all_groups_from_cache() {
+    use osars::models::{Campus, Group};
+    use osatui::{api::ApiClient, api::cache::CacheManager, config::Overrides};
+
+    let dir = tempfile::tempdir().unwrap();
+    // Nothing listens there, so every list has to come from the cache.
+    let api_url = "http://127.0.0.1:9";
+    let overrides = Overrides {
+        api_url: Some(api_url.to_string()),
+        cache_dir: Some(dir.path().join("cache")),
+        ..Overrides::default()
+    };
+    let config = Config::load_from(
+        dir.path().join("config.toml"),
+        Overrides::default(),
+        overrides,
+    )
+    .await
+    .unwrap();
+
+    let mut cache = CacheManager::new(dir.path().join("cache"), 3600, 3600)
+        .await
+        .unwrap();
+    cache.set_namespace(config.active_profile(), api_url, None);
+
+    let campus = |id, name: &str| Campus {
+        id,
+        name: name.to_string(),
+        college_id: 1,
+        groups: Vec::new(),
+    };
+    let group = |id, name: &str, campus_id| Group {
+        id,
+        name: name.to_string(),
+        campus_id,
+    };
+    let lists = [
+        (
+            "campuses_1",
+            serde_json::to_vec(&[campus(10, "Main"), campus(11, "North")]).unwrap(),
+        ),
+        (
+            "groups_10",
+            serde_json::to_vec(&[group(1, "ИСП-21-1", 10), group(2, "ИСП-21-2", 10)]).unwrap(),
+        ),
+        (
+            "groups_11",
+            serde_json::to_vec(&[group(3, "ПКС-22-1", 11)]).unwrap(),
+        ),
+    ];
+    for (key, data) in &lists {
+        cache.set_list(key, data).await.unwrap();
+    }
+
+    let api = ApiClient::new_base(config).await.unwrap();
+    let (campuses, groups) = api.get_all_groups(1).await.unwrap();
+    assert_eq!(campuses.len(), 2);
+    let found: Vec<(u32, u32)> = groups.iter().map(|g| (g.id, g.campus_id)).collect();
+    assert_eq!(found, vec![(1, 10), (2, 10), (3, 11)]);
+}
+
+#[test]
+fn test_selector_back_navigation() {
+    use osars::models::{Campus, College};
+    use osatui::{app::AppState, ui::screens::selector::SelectionStage};
+
+    let mut state = AppState::new(Config::default());
+    state.enter_selector(SelectionStage::College);
+    state.page_size = 2;
+    state.set_colleges(
+        (1..=5)
+            .map(|i| College {
+                college_id: i,
+                name: format!("College {}", i),
+                calls: Vec::new(),
+                campuses: Vec::new(),
+            })
+            .collect(),
+    );
+    state.next_page();
+    state.next_item();
+    let college = state.get_selected_college().cloned().unwrap();
+    assert_eq!(college.college_id, 4);
+
+    state.selected_college = Some(college);
+    state.push_stage(SelectionStage::Campus);
+    state.set_campuses(vec![Campus {
+        id: 10,
+        name: "Main".to_string(),
+        college_id: 4,
+        groups: Vec::new(),
+    }]);
+    state.push_filter('m');
+    state.selected_campus = state.get_selected_campus().cloned();
+    state.push_stage(SelectionStage::Group);
+    assert!(state.filter.is_empty());
+
+    assert!(state.pop_stage());
+    assert_eq!(state.selection_stage, SelectionStage::Campus);
+    assert_eq!(state.filter, "m");
+    assert!(state.selected_campus.is_none());
+    assert_eq!(state.selected_college.as_ref().unwrap().college_id, 4);
+
+    assert!(state.pop_stage());
+    assert_eq!(state.selection_stage, SelectionStage::College);
+    assert_eq!((state.page, state.selected_index), (1, 1));
+    assert_eq!(state.get_selected_college().unwrap().college_id, 4);
+    assert!(state.selected_college.is_none());
+
+    assert!(!state.pop_stage());
+}
+
+#[test]
+fn test_selector_back_from_group_search() {
+    use osars::models::Group;
+    use osatui::{app::AppState, ui::screens::selector::SelectionStage};
+
+    let groups = |campus_id: u32, ids: std::ops::Range<u32>| -> Vec<Group> {
+        ids.map(|id| Group {
+            id,
+            name: format!("Group {}", id),
+            campus_id,
+        })
+        .collect()
+    };
+
+    let mut state = AppState::new(Config::default());
+    state.enter_selector(SelectionStage::College);
+    state.push_stage(SelectionStage::Campus);
+    state.push_stage(SelectionStage::Group);
+    state.page_size = 2;
+    state.set_groups(groups(10, 100..105));
+    state.next_page();
+    assert_eq!(state.get_selected_group().unwrap().id, 102);
+
+    // The search replaces the list with the groups of every campus.
+    state.push_stage(SelectionStage::FindGroup);
+    state.set_groups([groups(10, 100..105), groups(11, 200..210)].concat());
+
+    assert!(state.pop_stage());
+    assert_eq!(state.selection_stage, SelectionStage::Group);
+    assert_eq!(state.groups.len(), 5);
+    assert_eq!(state.get_selected_group().unwrap().id, 102);
+}
+
+#[tokio::test]
+async fn test_selector_preselects_configured_entries() {
+    use osars::models::{Campus, Group};
+    use osatui::{app::AppState, config::Overrides, ui::screens::selector::SelectionStage};
+
+    let dir = tempfile::tempdir().unwrap();
+    let overrides = Overrides {
+        campus_id: Some(12),
+        group_id: Some(107),
+        ..Overrides::default()
+    };
+    let config = Config::load_from(
+        dir.path().join("config.toml"),
+        Overrides::default(),
+        overrides,
+    )
+    .await
+    .unwrap();
+
+    let mut state = AppState::new(config);
+    state.enter_selector(SelectionStage::Campus);
+    state.page_size = 3;
+    state.set_campuses(
+        (10..15)
+            .map(|id| Campus {
+                id,
+                name: format!("Campus {}", id),
+                college_id: 1,
+                groups: Vec::new(),
+            })
+            .collect(),
+    );
+    assert_eq!((state.page, state.selected_index), (0, 2));
+    assert_eq!(state.get_selected_campus().unwrap().id, 12);
+
+    state.push_stage(SelectionStage::Group);
+    state.set_groups(
+        (100..110)
+            .map(|id| Group {
+                id,
+                name: format!("Group {}", id),
+                campus_id: 12,
+            })
+            .collect(),
+    );
+    assert_eq!((state.page, state.selected_index), (2, 1));
+    assert_eq!(state.get_selected_group().unwrap().id, 107);
+    assert_eq!(
+        state.item_id(state.page_items()[1]),
+        Some(state.active_id())
+    );
+
+    // A list without the configured entry starts at the top.
+    state.set_groups(vec![Group {
+        id: 1,
+        name: "Other".to_string(),
+        campus_id: 12,
+    }]);
+    assert_eq!((state.page, state.selected_index), (0, 0));
+}
+
+#[tokio::test]
+async fn test_recent_and_favorite_groups() {
+    use osatui::config::{MAX_RECENT, Overrides, SavedGroup};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let mut config = Config::load_from(path.clone(), Overrides::default(), Overrides::default())
+        .await
+        .unwrap();
+    assert!(config.saved_groups().is_empty());
+
+    let url = config.api_url().to_string();
+    let group = |id: u32| SavedGroup {
+        url: url.clone(),
+        college_id: 1,
+        campus_id: 2,
+        group_id: id,
+        group: format!("ИСП-{}", id),
+        campus: "Main".to_string(),
+        college: String::new(),
+    };
+    let (first, second) = (group(10), group(20));
+
+    config.push_recent(first.clone());
+    config.push_recent(second.clone());
+    config.push_recent(first.clone());
+    assert_eq!(config.recent(), [first.clone(), second.clone()]);
+
+    // Starred groups come first and are not repeated among the recent ones.
+    assert!(config.toggle_favorite(second.clone()));
+    assert_eq!(config.saved_groups(), [&second, &first]);
+    assert!(config.is_favorite(&second));
+
+    for id in 100..100 + MAX_RECENT as u32 {
+        config.push_recent(group(id));
+    }
+    assert_eq!(config.recent().len(), MAX_RECENT);
+    assert_eq!(config.recent()[0].group_id, 100 + MAX_RECENT as u32 - 1);
+    config.save().await.unwrap();
+
+    let mut saved = Config::load_from(path, Overrides::default(), Overrides::default())
+        .await
+        .unwrap();
+    assert_eq!(saved.recent(), config.recent());
+    assert_eq!(saved.favorites(), std::slice::from_ref(&second));
+    assert!(!saved.toggle_favorite(second));
+    assert!(saved.favorites().is_empty());
+
+    let current = SavedGroup {
+        group_id: saved.group_id(),
+        college_id: saved.college_id(),
+        campus_id: saved.campus_id(),
+        ..group(0)
+    };
+    assert!(saved.is_current_group(&current));
+}
+
+#[tokio::test]
+async fn test_broken_key_sequence_is_replayed() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use osatui::{
+        app::{App, AppMode},
+        config::Overrides,
+        ui::screens::selector::SelectionStage,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let mut table: toml::Table =
+        toml::from_str(&toml::to_string(&Config::default()).unwrap()).unwrap();
+    table.insert(
+        "keymap".to_string(),
+        toml::from_str("preset = \"vim\"").unwrap(),
+    );
+    std::fs::write(&path, toml::to_string(&table).unwrap()).unwrap();
+    let overrides = Overrides {
+        api_url: Some("http://127.0.0.1:9".to_string()),
+        cache_dir: Some(dir.path().join("cache")),
+        ..Overrides::default()
+    };
+    let config = Config::load_from(path, Overrides::default(), overrides)
+        .await
+        .unwrap();
+
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    let mut app = App::new(config).await.unwrap();
+    app.state_mut().enter_selector(SelectionStage::College);
+
+    // `g g` jumps to the first item and types nothing.
+    app.handle_key_event(key('g')).await.unwrap();
+    app.handle_key_event(key('g')).await.unwrap();
+    assert!(app.state().filter.is_empty());
+
+    // A `g` that does not become `g g` still starts the filter.
+    app.handle_key_event(key('g')).await.unwrap();
+    assert!(app.state().filter.is_empty());
+    app.handle_key_event(key('r')).await.unwrap();
+    assert_eq!(app.state().filter, "gr");
+    assert!(app.state().filter_focused);
+    assert_eq!(app.state().mode, AppMode::Selector);
+}
+
+#[tokio::test]
+async fn test_cli_today_rejects_date() {
+    use osatui::cli::{Command, OutputFormat, run};
+
+    let tomorrow = AppDate::today().next();
+    let error = run(
+        Command::Today,
+        Some(tomorrow),
+        OutputFormat::Plain,
+        Config::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("--date"), "{}", error);
+}